@@ -0,0 +1,134 @@
+//! Access to the mod.io API.
+//!
+//! [`ModioBackend`] abstracts over how mods are looked up, so that checks can run against the real
+//! mod.io API ([`HttpBackend`]) or against an in-memory set of mods ([`FixtureBackend`]).
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::modio::{Game, Mod, Modfile, User};
+
+mod fixture;
+mod http;
+mod rate_limit;
+mod retry;
+
+pub use fixture::FixtureBackend;
+pub use http::{default_api_base, HttpBackend, MODIO_API_BASE};
+pub use rate_limit::RateLimitHook;
+pub use retry::RetryPolicy;
+
+#[derive(Debug, Error)]
+pub enum BackendError {
+    #[error(transparent)]
+    Request(#[from] reqwest::Error),
+    #[error("mod.io responded with status {status}")]
+    Status { status: u16 },
+    #[error("mod.io responded with status {status} (error_ref {}): {error}", error.error_ref)]
+    Api { status: u16, error: ApiError },
+}
+
+impl BackendError {
+    /// HTTP status code of the failed response, if a response was received at all.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            BackendError::Request(error) => error.status().map(|code| code.as_u16()),
+            BackendError::Status { status } | BackendError::Api { status, .. } => Some(*status),
+        }
+    }
+
+    /// The error reported by mod.io, if the response contained one.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            BackendError::Api { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Classify a failed response by its `status` and `body`, which usually contains mod.io's error
+    /// envelope.
+    pub(crate) fn from_response(status: u16, body: &str) -> Self {
+        #[derive(Deserialize)]
+        struct Envelope {
+            error: ApiError,
+        }
+
+        match serde_json::from_str::<Envelope>(body) {
+            Ok(Envelope { error }) => BackendError::Api { status, error },
+            Err(_) => BackendError::Status { status },
+        }
+    }
+}
+
+/// Error reported by mod.io, see <https://docs.mod.io/restapi/docs/errors>.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ApiError {
+    /// HTTP status code of the response.
+    pub code: u16,
+    /// mod.io specific error code, e.g. [`MODIO_ERROR_REF_TOKEN_EXPIRED`](crate::MODIO_ERROR_REF_TOKEN_EXPIRED).
+    pub error_ref: u32,
+    pub message: String,
+    /// Validation errors by field name.
+    #[serde(default)]
+    pub errors: BTreeMap<String, String>,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        for (field, message) in &self.errors {
+            write!(f, "; {field}: {message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Selects mods by one of their identifiers, mapping to mod.io's `name_id-in` and `id-in` filters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModFilter {
+    NameIds(Vec<String>),
+    Ids(Vec<u32>),
+}
+
+impl ModFilter {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            ModFilter::NameIds(name_ids) => name_ids.is_empty(),
+            ModFilter::Ids(ids) => ids.is_empty(),
+        }
+    }
+
+    pub fn matches(&self, r#mod: &Mod) -> bool {
+        match self {
+            ModFilter::NameIds(name_ids) => name_ids.contains(&r#mod.name_id),
+            ModFilter::Ids(ids) => ids.contains(&r#mod.id),
+        }
+    }
+}
+
+pub trait ModioBackend {
+    /// Fetch the user the access token belongs to.
+    fn me(&self) -> impl Future<Output = Result<User, BackendError>> + Send;
+
+    /// Fetch the game with the given id or `name_id`, `None` if it doesn't exist.
+    fn game(&self, game: &str) -> impl Future<Output = Result<Option<Game>, BackendError>> + Send;
+
+    /// Fetch all mods of `game_id` matching `filter`, across all result pages.
+    fn mods(
+        &self,
+        game_id: u32,
+        filter: &ModFilter,
+    ) -> impl Future<Output = Result<Vec<Mod>, BackendError>> + Send;
+
+    /// Fetch modfile `modfile_id` of mod `mod_id`, `None` if it doesn't exist.
+    fn modfile(
+        &self,
+        game_id: u32,
+        mod_id: u32,
+        modfile_id: u32,
+    ) -> impl Future<Output = Result<Option<Modfile>, BackendError>> + Send;
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use crate::backend::{BackendError, ModFilter, ModioBackend};
+use crate::modio::{Game, Mod, Modfile, User, MODIO_DRG_ID, MODIO_DRG_NAME_ID};
+
+/// In-memory [`ModioBackend`] serving a fixed set of mods, for testing without hitting mod.io.
+///
+/// ```
+/// use modio_modcheck::backend::FixtureBackend;
+/// use modio_modcheck::{Mod, ModChecker, MODIO_DRG_ID, MOD_STATUS_ACCEPTED};
+///
+/// let backend = FixtureBackend::new()
+///     .with_mod(MODIO_DRG_ID, Mod {
+///         id: 1,
+///         name_id: "sandbox-utilities".to_string(),
+///         status: MOD_STATUS_ACCEPTED,
+///         visible: 1,
+///         profile_url: "https://mod.io/g/drg/m/sandbox-utilities".to_string(),
+///     })
+///     .with_error("broken", 500);
+/// let checker = ModChecker::new(backend, MODIO_DRG_ID);
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// assert!(checker.check("https://mod.io/g/drg/m/sandbox-utilities").await.result.is_ok());
+/// assert!(checker.check("https://mod.io/g/drg/m/broken").await.result.is_err());
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct FixtureBackend {
+    user: Option<User>,
+    games: Vec<Game>,
+    mods: Vec<(u32, Mod)>,
+    modfiles: Vec<Modfile>,
+    /// Error statuses of mod lookups, by `name_id` or mod id.
+    errors: HashMap<String, u16>,
+    /// Error statuses of modfile lookups, by modfile id.
+    modfile_errors: HashMap<u32, u16>,
+    me_error: Option<u16>,
+}
+
+impl Default for FixtureBackend {
+    fn default() -> Self {
+        let drg = Game {
+            id: MODIO_DRG_ID,
+            name_id: MODIO_DRG_NAME_ID.to_string(),
+            name: "Deep Rock Galactic".to_string(),
+        };
+        FixtureBackend {
+            user: None,
+            games: vec![drg],
+            mods: vec![],
+            modfiles: vec![],
+            errors: HashMap::new(),
+            modfile_errors: HashMap::new(),
+            me_error: None,
+        }
+    }
+}
+
+impl FixtureBackend {
+    /// A backend knowing only of Deep Rock Galactic, without any mods, rejecting the access token.
+    pub fn new() -> Self {
+        FixtureBackend::default()
+    }
+
+    /// Accept the access token as belonging to `user`.
+    pub fn with_user(mut self, user: User) -> Self {
+        self.user = Some(user);
+        self
+    }
+
+    /// Add a game.
+    pub fn with_game(mut self, game: Game) -> Self {
+        self.games.push(game);
+        self
+    }
+
+    /// Add a mod belonging to `game_id`.
+    pub fn with_mod(mut self, game_id: u32, r#mod: Mod) -> Self {
+        self.mods.push((game_id, r#mod));
+        self
+    }
+
+    /// Add a modfile, belonging to the mod with id `modfile.mod_id`.
+    pub fn with_modfile(mut self, modfile: Modfile) -> Self {
+        self.modfiles.push(modfile);
+        self
+    }
+
+    /// Respond to lookups including the mod with the given `name_id` or id with an error response
+    /// of the given HTTP `status`.
+    pub fn with_error(mut self, mod_ref: impl ToString, status: u16) -> Self {
+        self.errors.insert(mod_ref.to_string(), status);
+        self
+    }
+
+    /// Respond to lookups of the modfile with `modfile_id` with an error response of the given HTTP
+    /// `status`.
+    pub fn with_modfile_error(mut self, modfile_id: u32, status: u16) -> Self {
+        self.modfile_errors.insert(modfile_id, status);
+        self
+    }
+
+    /// Respond to the access token check with an error response of the given HTTP `status`, even if
+    /// a user is set.
+    pub fn with_me_error(mut self, status: u16) -> Self {
+        self.me_error = Some(status);
+        self
+    }
+}
+
+impl ModioBackend for FixtureBackend {
+    async fn me(&self) -> Result<User, BackendError> {
+        if let Some(status) = self.me_error {
+            return Err(BackendError::Status { status });
+        }
+        self.user.clone().ok_or(BackendError::Status { status: 401 })
+    }
+
+    async fn game(&self, game: &str) -> Result<Option<Game>, BackendError> {
+        Ok(self.games.iter().find(|g| g.name_id == game || g.id.to_string() == game).cloned())
+    }
+
+    async fn mods(&self, game_id: u32, filter: &ModFilter) -> Result<Vec<Mod>, BackendError> {
+        let status = match filter {
+            ModFilter::NameIds(name_ids) => {
+                name_ids.iter().find_map(|name_id| self.errors.get(name_id))
+            }
+            ModFilter::Ids(ids) => ids.iter().find_map(|id| self.errors.get(&id.to_string())),
+        };
+        if let Some(&status) = status {
+            return Err(BackendError::Status { status });
+        }
+
+        Ok(self
+            .mods
+            .iter()
+            .filter(|(game, r#mod)| *game == game_id && filter.matches(r#mod))
+            .map(|(_, r#mod)| r#mod.clone())
+            .collect())
+    }
+
+    async fn modfile(
+        &self,
+        _game_id: u32,
+        mod_id: u32,
+        modfile_id: u32,
+    ) -> Result<Option<Modfile>, BackendError> {
+        if let Some(&status) = self.modfile_errors.get(&modfile_id) {
+            return Err(BackendError::Status { status });
+        }
+        Ok(self
+            .modfiles
+            .iter()
+            .find(|modfile| modfile.mod_id == mod_id && modfile.id == modfile_id)
+            .cloned())
+    }
+}
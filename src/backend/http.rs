@@ -0,0 +1,193 @@
+use serde::de::DeserializeOwned;
+use tracing::*;
+
+use crate::backend::rate_limit::{RateLimitHook, RateLimiter};
+use crate::backend::{BackendError, ModFilter, ModioBackend, RetryPolicy};
+use crate::modio::{Game, Games, Mod, Modfile, Mods, User, MODIO_PAGE_LIMIT};
+
+/// Global mod.io API host, not tied to a user.
+pub const MODIO_API_BASE: &str = "https://api.mod.io/v1";
+
+/// Visibilities of mods to look up, mod.io only returns public mods unless asked to.
+const MOD_VISIBLE_IN: &str = "0,1";
+
+/// Statuses of mods to look up, mod.io only returns accepted mods unless asked to.
+const MOD_STATUS_IN: &str = "0,1,3";
+
+/// How often a request is sent again after being rejected by mod.io's rate limit.
+const MAX_RATE_LIMITED_ATTEMPTS: u32 = 3;
+
+/// [`ModioBackend`] talking to the mod.io REST API.
+#[derive(Debug)]
+pub struct HttpBackend {
+    client: reqwest::Client,
+    api_base: String,
+    token: String,
+    rate_limit: RateLimiter,
+    retry: RetryPolicy,
+}
+
+impl HttpBackend {
+    pub fn new(client: reqwest::Client, user_id: u64, token: impl Into<String>) -> Self {
+        HttpBackend {
+            client,
+            api_base: default_api_base(user_id),
+            token: token.into(),
+            rate_limit: RateLimiter::default(),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Like [`HttpBackend::new`], but talking to the global [`MODIO_API_BASE`] API host, for when
+    /// the user id isn't known.
+    pub fn global(client: reqwest::Client, token: impl Into<String>) -> Self {
+        HttpBackend {
+            client,
+            api_base: MODIO_API_BASE.to_string(),
+            token: token.into(),
+            rate_limit: RateLimiter::default(),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Use `api_base` instead of the per-user `https://u-{user_id}.modapi.io/v1` API host, e.g. to
+    /// talk to mod.io's test environment or a local stub server.
+    pub fn with_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = api_base.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Call `on_wait` when requests are paused, see [`RateLimitHook`].
+    pub fn on_rate_limit(mut self, on_wait: RateLimitHook) -> Self {
+        self.rate_limit.set_hook(on_wait);
+        self
+    }
+
+    /// Retry requests failing with transient errors according to `retry`.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    /// `GET` `url` with `query` and decode the JSON response, waiting as required by mod.io's rate
+    /// limit and retrying transient failures.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, BackendError> {
+        let mut rate_limited = 1;
+        let mut attempt = 1;
+        loop {
+            self.rate_limit.ready().await;
+            let res = self
+                .client
+                .get(url)
+                .query(query)
+                .header("accept", "application/json")
+                .bearer_auth(&self.token)
+                .send()
+                .await;
+            let error = match res {
+                Ok(res) => {
+                    if self.rate_limit.update(res.status(), res.headers())
+                        && rate_limited < MAX_RATE_LIMITED_ATTEMPTS
+                    {
+                        debug!(rate_limited, "rate limited, retrying <{url}>");
+                        rate_limited += 1;
+                        continue;
+                    }
+                    let status = res.status();
+                    if status.is_success() {
+                        return Ok(res.json().await?);
+                    }
+                    let body = res.text().await.unwrap_or_default();
+                    BackendError::from_response(status.as_u16(), &body)
+                }
+                Err(error) => error.into(),
+            };
+
+            if attempt >= self.retry.max_attempts || !self.retry.is_retryable(&error) {
+                return Err(error);
+            }
+            let delay = self.retry.delay(attempt);
+            debug!(?error, attempt, ?delay, "transient failure, retrying <{url}>");
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+pub fn default_api_base(user_id: u64) -> String {
+    format!("https://u-{user_id}.modapi.io/v1")
+}
+
+impl ModioBackend for HttpBackend {
+    async fn me(&self) -> Result<User, BackendError> {
+        self.get(&format!("{}/me", self.api_base), &[]).await
+    }
+
+    async fn game(&self, game: &str) -> Result<Option<Game>, BackendError> {
+        let HttpBackend { api_base, .. } = self;
+        if let Ok(game_id) = game.parse::<u32>() {
+            return match self.get(&format!("{api_base}/games/{game_id}"), &[]).await {
+                Ok(game) => Ok(Some(game)),
+                Err(error) if error.status() == Some(404) => Ok(None),
+                Err(error) => Err(error),
+            };
+        }
+
+        let games: Games =
+            self.get(&format!("{api_base}/games"), &[("name_id", game.to_string())]).await?;
+        Ok(games.data.into_iter().find(|g| g.name_id == game))
+    }
+
+    async fn mods(&self, game_id: u32, filter: &ModFilter) -> Result<Vec<Mod>, BackendError> {
+        let HttpBackend { api_base, .. } = self;
+        let url = format!("{api_base}/games/{game_id}/mods");
+        let filter = match filter {
+            ModFilter::NameIds(name_ids) => ("name_id-in", name_ids.join(",")),
+            ModFilter::Ids(ids) => {
+                ("id-in", ids.iter().map(u32::to_string).collect::<Vec<_>>().join(","))
+            }
+        };
+
+        let mut mods = vec![];
+        let mut offset = 0;
+        loop {
+            let query = [
+                filter.clone(),
+                ("visible-in", MOD_VISIBLE_IN.to_string()),
+                ("status-in", MOD_STATUS_IN.to_string()),
+                ("_offset", offset.to_string()),
+                ("_limit", MODIO_PAGE_LIMIT.to_string()),
+            ];
+            let page: Mods = self.get(&url, &query).await?;
+            trace!(offset, count = page.result_count, total = page.result_total, "fetched page");
+            mods.extend(page.data);
+            offset = page.result_offset + page.result_count;
+            if page.result_count == 0 || offset >= page.result_total {
+                return Ok(mods);
+            }
+        }
+    }
+
+    async fn modfile(
+        &self,
+        game_id: u32,
+        mod_id: u32,
+        modfile_id: u32,
+    ) -> Result<Option<Modfile>, BackendError> {
+        let HttpBackend { api_base, .. } = self;
+        let url = format!("{api_base}/games/{game_id}/mods/{mod_id}/files/{modfile_id}");
+        match self.get(&url, &[]).await {
+            Ok(modfile) => Ok(Some(modfile)),
+            Err(error) if error.status() == Some(404) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+}
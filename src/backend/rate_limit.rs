@@ -0,0 +1,109 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use tokio::time::Instant;
+use tracing::*;
+
+/// Wait used when mod.io tells us to back off without saying for how long.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Least amount by which a further response may extend an ongoing pause, so concurrent responses
+/// reporting the same exhausted rate limit don't each push it back slightly and announce it again.
+const MIN_PAUSE_EXTENSION: Duration = Duration::from_secs(1);
+
+/// Callback invoked with the wait duration whenever mod.io's rate limit pauses requests, once per
+/// pause, e.g. to tell the user why nothing is happening.
+pub type RateLimitHook = Arc<dyn Fn(Duration) + Send + Sync>;
+
+/// Paces requests according to the `X-RateLimit-*` and `Retry-After` headers of mod.io responses.
+///
+/// Once mod.io reports that no requests remain (or responds with `429 Too Many Requests`), all
+/// requests are held back until the advertised retry time has passed.
+#[derive(Default)]
+pub(crate) struct RateLimiter {
+    state: Mutex<State>,
+    on_wait: Option<RateLimitHook>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    blocked_until: Option<Instant>,
+    /// Last `blocked_until` reported to `on_wait`, so each wait is only reported once.
+    announced: Option<Instant>,
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimiter").field("state", &self.state).finish()
+    }
+}
+
+impl RateLimiter {
+    pub(crate) fn set_hook(&mut self, on_wait: RateLimitHook) {
+        self.on_wait = Some(on_wait);
+    }
+
+    /// Wait until requests are allowed again.
+    pub(crate) async fn ready(&self) {
+        loop {
+            let until = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                match state.blocked_until {
+                    Some(until) if until > now => {
+                        if state.announced != Some(until) {
+                            state.announced = Some(until);
+                            if let Some(on_wait) = &self.on_wait {
+                                on_wait(until - now);
+                            }
+                        }
+                        until
+                    }
+                    _ => return,
+                }
+            };
+            tokio::time::sleep_until(until).await;
+        }
+    }
+
+    /// Record the rate limit state advertised by a response. Returns `true` if the request was
+    /// rejected because of the rate limit and should be sent again.
+    pub(crate) fn update(&self, status: StatusCode, headers: &HeaderMap) -> bool {
+        let remaining = header_u64(headers, "x-ratelimit-remaining");
+        let retry_after = header_u64(headers, "x-ratelimit-retryafter").map(Duration::from_secs);
+        trace!(?status, ?remaining, ?retry_after, "rate limit headers");
+
+        let rate_limited = status == StatusCode::TOO_MANY_REQUESTS;
+        let wait = if rate_limited {
+            header_u64(headers, RETRY_AFTER.as_str())
+                .map(Duration::from_secs)
+                .or(retry_after)
+                .unwrap_or(DEFAULT_RETRY_AFTER)
+        } else if remaining == Some(0) {
+            retry_after.unwrap_or(DEFAULT_RETRY_AFTER)
+        } else {
+            return false;
+        };
+
+        let now = Instant::now();
+        let until = now + wait;
+        let mut state = self.state.lock().unwrap();
+        let extends = match state.blocked_until {
+            Some(current) if current > now => until >= current + MIN_PAUSE_EXTENSION,
+            _ => true,
+        };
+        if extends {
+            debug!(?wait, "rate limited by mod.io");
+            state.blocked_until = Some(until);
+        }
+
+        rate_limited
+    }
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
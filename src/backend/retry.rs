@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use crate::backend::BackendError;
+
+/// When and how often requests failing with transient errors are sent again.
+///
+/// Timeouts, connection failures and responses with one of the `retryable_statuses` are retried
+/// up to `max_attempts` attempts in total, waiting an exponentially growing, jittered delay
+/// between attempts.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts per request, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for every further retry.
+    pub base_delay: Duration,
+    /// Upper bound for the delay between two attempts.
+    pub max_delay: Duration,
+    /// HTTP status codes which are considered transient.
+    pub retryable_statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            retryable_statuses: vec![408, 500, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry failed requests.
+    pub fn none() -> Self {
+        RetryPolicy { max_attempts: 1, ..RetryPolicy::default() }
+    }
+
+    pub(crate) fn is_retryable(&self, error: &BackendError) -> bool {
+        match (error.status(), error) {
+            (Some(status), _) => self.retryable_statuses.contains(&status),
+            (None, BackendError::Request(error)) => {
+                error.is_timeout() || error.is_connect() || error.is_request()
+            }
+            (None, _) => false,
+        }
+    }
+
+    /// Delay after the failed `attempt` (starting at 1): half of the exponential backoff is fixed,
+    /// the other half is random so that concurrent requests don't retry in lockstep.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let backoff =
+            self.base_delay.saturating_mul(1 << (attempt - 1).min(16)).min(self.max_delay);
+        backoff / 2 + backoff.mul_f64(fastrand::f64() / 2.0)
+    }
+}
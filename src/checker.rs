@@ -0,0 +1,420 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use thiserror::Error;
+use tokio::sync::Semaphore;
+use tracing::*;
+
+use crate::backend::{
+    BackendError, HttpBackend, ModFilter, ModioBackend, RateLimitHook, RetryPolicy,
+};
+use crate::error::{AuthError, GameError, ModCheckError, OutcomeKind};
+use crate::modio::{
+    Game, Mod, User, MODIO_DRG_ID, MODIO_DRG_NAME_ID, MOD_STATUS_DELETED, MOD_STATUS_NOT_ACCEPTED,
+};
+use crate::url::ModRef;
+
+#[derive(Debug, Error)]
+pub enum BuildError {
+    #[error("mod.io OAuth2 access token is required")]
+    MissingToken,
+    #[error("concurrency must be at least 1")]
+    ZeroConcurrency,
+}
+
+/// Number of requests sent concurrently by default.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Maximum number of mods looked up with a single request.
+pub const BATCH_SIZE: usize = 50;
+
+/// Builder for a [`ModChecker`] using the [`HttpBackend`], see [`ModChecker::builder`].
+#[derive(Default, Clone)]
+pub struct ModCheckerBuilder {
+    user_id: Option<u64>,
+    token: Option<String>,
+    game_id: Option<u32>,
+    game_name_id: Option<String>,
+    api_base: Option<String>,
+    global_api_base: Option<String>,
+    concurrency: Option<usize>,
+    client: Option<reqwest::Client>,
+    on_rate_limit: Option<RateLimitHook>,
+    retry_policy: Option<RetryPolicy>,
+}
+
+impl ModCheckerBuilder {
+    /// mod.io user id, used to pick the per-user API host instead of the global one.
+    pub fn user_id(mut self, user_id: u64) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    /// OAuth2 access token, surrounding whitespace is trimmed.
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into().trim().to_string());
+        self
+    }
+
+    /// mod.io game id, defaults to [`MODIO_DRG_ID`].
+    pub fn game_id(mut self, game_id: u32) -> Self {
+        self.game_id = Some(game_id);
+        self
+    }
+
+    /// `name_id` of the game, to report URLs of mods of other games as
+    /// [`ModCheckError::GameMismatch`]. Only known for [`MODIO_DRG_ID`] if not given.
+    pub fn game_name_id(mut self, game_name_id: impl Into<String>) -> Self {
+        self.game_name_id = Some(game_name_id.into());
+        self
+    }
+
+    /// Check mods of `game`, e.g. as found by [`ModChecker::find_game`], setting both
+    /// [`game_id`](Self::game_id) and [`game_name_id`](Self::game_name_id).
+    pub fn game(self, game: Game) -> Self {
+        self.game_id(game.id).game_name_id(game.name_id)
+    }
+
+    /// mod.io API base URL, defaults to the per-user `https://u-{user_id}.modapi.io/v1`. `{user_id}`
+    /// is replaced by the user id; such a URL is only used once the user id is known.
+    pub fn api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = Some(api_base.into());
+        self
+    }
+
+    /// mod.io API base URL used without a user id, e.g. to look up the user of the access token,
+    /// defaults to [`MODIO_API_BASE`](crate::backend::MODIO_API_BASE).
+    pub fn global_api_base(mut self, global_api_base: impl Into<String>) -> Self {
+        self.global_api_base = Some(global_api_base.into());
+        self
+    }
+
+    /// Maximum number of requests sent at the same time, defaults to [`DEFAULT_CONCURRENCY`].
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// HTTP client to reuse, a new one is created if not provided.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Call `on_wait` when requests are paused, see [`RateLimitHook`].
+    pub fn on_rate_limit(mut self, on_wait: impl Fn(Duration) + Send + Sync + 'static) -> Self {
+        self.on_rate_limit = Some(Arc::new(on_wait));
+        self
+    }
+
+    /// How transient mod.io failures are retried, defaults to [`RetryPolicy::default`].
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    pub fn build(self) -> Result<ModChecker, BuildError> {
+        let client = self.client.unwrap_or_default();
+        let token = self.token.ok_or(BuildError::MissingToken)?;
+        let mut backend = match (self.user_id, self.api_base) {
+            (Some(user_id), Some(api_base)) => HttpBackend::new(client, user_id, token)
+                .with_api_base(api_base.replace("{user_id}", &user_id.to_string())),
+            (Some(user_id), None) => HttpBackend::new(client, user_id, token),
+            (None, Some(api_base)) if !api_base.contains("{user_id}") => {
+                HttpBackend::global(client, token).with_api_base(api_base)
+            }
+            (None, _) => match self.global_api_base {
+                Some(global_api_base) => {
+                    HttpBackend::global(client, token).with_api_base(global_api_base)
+                }
+                None => HttpBackend::global(client, token),
+            },
+        };
+        if let Some(retry_policy) = self.retry_policy {
+            backend = backend.with_retry_policy(retry_policy);
+        }
+        if let Some(on_wait) = self.on_rate_limit {
+            backend = backend.on_rate_limit(on_wait);
+        }
+        let concurrency = match self.concurrency {
+            Some(0) => return Err(BuildError::ZeroConcurrency),
+            Some(concurrency) => concurrency,
+            None => DEFAULT_CONCURRENCY,
+        };
+        let mut checker = ModChecker::new(backend, self.game_id.unwrap_or(MODIO_DRG_ID))
+            .with_concurrency(concurrency);
+        if let Some(game_name_id) = self.game_name_id {
+            checker.game_name_id = Some(game_name_id);
+        }
+        Ok(checker)
+    }
+}
+
+/// Result of checking a single mod URL.
+#[derive(Debug)]
+pub struct ModCheck {
+    pub url: String,
+    /// The mod referenced by `url`, `None` if it isn't a mod reference.
+    pub mod_ref: Option<ModRef>,
+    /// The mod returned by mod.io for `url`, even if the check failed.
+    pub found: Option<Mod>,
+    pub result: Result<Mod, ModCheckError>,
+}
+
+impl ModCheck {
+    pub fn kind(&self) -> OutcomeKind {
+        match &self.result {
+            Ok(_) => OutcomeKind::Ok,
+            Err(e) => e.kind(),
+        }
+    }
+}
+
+/// Checks mod URLs against mod.io through a [`ModioBackend`].
+#[derive(Debug)]
+pub struct ModChecker<B = HttpBackend> {
+    backend: B,
+    game_id: u32,
+    /// `name_id` of the game, if known, to reject URLs of mods of other games.
+    game_name_id: Option<String>,
+    concurrency: usize,
+    /// Permits for requests in flight, limiting them to `concurrency`.
+    requests: Semaphore,
+}
+
+impl ModChecker {
+    pub fn builder() -> ModCheckerBuilder {
+        ModCheckerBuilder::default()
+    }
+}
+
+impl<B: ModioBackend> ModChecker<B> {
+    /// Check mods of `game_id` through `backend`. URLs of mods of other games are only recognised
+    /// for [`MODIO_DRG_ID`], use [`ModChecker::with_game`] for other games.
+    pub fn new(backend: B, game_id: u32) -> Self {
+        let game_name_id = (game_id == MODIO_DRG_ID).then(|| MODIO_DRG_NAME_ID.to_string());
+        ModChecker {
+            backend,
+            game_id,
+            game_name_id,
+            concurrency: DEFAULT_CONCURRENCY,
+            requests: Semaphore::new(DEFAULT_CONCURRENCY),
+        }
+    }
+
+    /// Check mods of `game`, e.g. as found by [`ModChecker::find_game`].
+    pub fn with_game(mut self, game: Game) -> Self {
+        self.game_id = game.id;
+        self.game_name_id = Some(game.name_id);
+        self
+    }
+
+    /// Check that the access token is valid and belongs to the user with `user_id`, if given, and
+    /// return the user it belongs to.
+    pub async fn verify_token(&self, user_id: Option<u64>) -> Result<User, AuthError> {
+        let user = self.backend.me().await?;
+        match user_id {
+            Some(user_id) if user.id != user_id => Err(AuthError::WrongUser { user_id, user }),
+            _ => Ok(user),
+        }
+    }
+
+    /// Look up a game by its id or `name_id`.
+    pub async fn find_game(&self, game: &str) -> Result<Game, GameError> {
+        match self.backend.game(game).await {
+            Ok(Some(found)) => Ok(found),
+            Ok(None) => Err(GameError::NotFound { game: game.to_string() }),
+            Err(error) => Err(GameError::ModioError { game: game.to_string(), error }),
+        }
+    }
+
+    /// Send up to `concurrency` requests at the same time in [`ModChecker::check_all`].
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self.requests = Semaphore::new(self.concurrency);
+        self
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Look up the mods referenced by `urls` with batched queries by `name_id` and by mod id, and
+    /// classify each URL by the mods found for it.
+    async fn check_batch(&self, urls: &[&str]) -> Vec<ModCheck> {
+        let parsed = urls.iter().map(|url| ModRef::parse(url)).collect::<Vec<_>>();
+
+        let mut name_ids = vec![];
+        let mut ids = vec![];
+        for mod_ref in parsed.iter().flatten().filter(|mod_ref| self.other_game(mod_ref).is_none())
+        {
+            if let Some(name_id) = &mod_ref.name_id {
+                if !name_ids.contains(name_id) {
+                    name_ids.push(name_id.clone());
+                }
+            }
+            if let Some(mod_id) = mod_ref.mod_id {
+                if !ids.contains(&mod_id) {
+                    ids.push(mod_id);
+                }
+            }
+        }
+        let (by_name, by_id) = futures_util::join!(
+            self.lookup(ModFilter::NameIds(name_ids)),
+            self.lookup(ModFilter::Ids(ids))
+        );
+
+        let (by_name, by_id) = (&by_name, &by_id);
+        let checks = urls.iter().zip(parsed).map(|(url, mod_ref)| async move {
+            let Some(mod_ref) = mod_ref else {
+                let result = Err(ModCheckError::InvalidModUrl { url: url.to_string() });
+                return ModCheck { url: url.to_string(), mod_ref: None, found: None, result };
+            };
+            if let Some(game) = self.other_game(&mod_ref) {
+                let result = Err(ModCheckError::GameMismatch { url: url.to_string(), game });
+                return ModCheck {
+                    url: url.to_string(),
+                    mod_ref: Some(mod_ref),
+                    found: None,
+                    result,
+                };
+            }
+            let (found, result) = match find_mod(url, &mod_ref, by_name, by_id) {
+                Ok(r#mod) => {
+                    let result = self.verify_mod(url, &mod_ref, r#mod, by_name).await;
+                    (Some(r#mod.clone()), result.map(|()| r#mod.clone()))
+                }
+                Err(e) => (None, Err(e)),
+            };
+            ModCheck { url: url.to_string(), mod_ref: Some(mod_ref), found, result }
+        });
+        futures_util::stream::iter(checks).buffered(self.concurrency).collect().await
+    }
+
+    /// The game of `mod_ref`, if it's known to be another game than the checked one.
+    fn other_game(&self, mod_ref: &ModRef) -> Option<String> {
+        match (&mod_ref.game, &self.game_name_id) {
+            (Some(game), Some(expected)) if game != expected => Some(game.clone()),
+            _ => None,
+        }
+    }
+
+    async fn lookup(&self, filter: ModFilter) -> Result<Vec<Mod>, Arc<BackendError>> {
+        if filter.is_empty() {
+            return Ok(vec![]);
+        }
+        let _permit = self.requests.acquire().await.expect("semaphore is never closed");
+        self.backend.mods(self.game_id, &filter).await.map_err(|error| {
+            debug!(?error, ?filter, "batched request failed");
+            Arc::new(error)
+        })
+    }
+
+    /// Check that `r#mod` found for `mod_ref` is live, that the pinned modfile (if any) still
+    /// belongs to it, and that it is still known by the name_id of `mod_ref` (if any).
+    async fn verify_mod(
+        &self,
+        url: &str,
+        mod_ref: &ModRef,
+        r#mod: &Mod,
+        by_name: &Result<Vec<Mod>, Arc<BackendError>>,
+    ) -> Result<(), ModCheckError> {
+        let url = url.to_string();
+        match r#mod.status {
+            MOD_STATUS_DELETED => return Err(ModCheckError::Deleted { url }),
+            MOD_STATUS_NOT_ACCEPTED => return Err(ModCheckError::PendingModeration { url }),
+            _ if r#mod.visible == 0 => return Err(ModCheckError::Hidden { url }),
+            _ => {}
+        }
+
+        if let Some(modfile_id) = mod_ref.modfile_id {
+            let mod_id = r#mod.id;
+            let permit = self.requests.acquire().await.expect("semaphore is never closed");
+            let modfile = self.backend.modfile(self.game_id, mod_id, modfile_id).await;
+            drop(permit);
+            let modfile = match modfile {
+                Ok(modfile) => modfile,
+                Err(error) => return Err(ModCheckError::modio(url, Arc::new(error))),
+            };
+            if modfile.is_none_or(|modfile| modfile.mod_id != mod_id) {
+                return Err(ModCheckError::ModfileRemoved { url, mod_id, modfile_id });
+            }
+        }
+
+        let Some(name_id) = &mod_ref.name_id else {
+            return Ok(());
+        };
+        let known_by_name = by_name
+            .as_ref()
+            .is_ok_and(|by_name| by_name.iter().any(|m| m.id == r#mod.id && &m.name_id == name_id));
+        if !known_by_name {
+            return Err(ModCheckError::Renamed {
+                old_url: url,
+                new_url: r#mod.profile_url.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Check that the mod referenced by `url` can still be found on mod.io.
+    pub async fn check(&self, url: &str) -> ModCheck {
+        self.check_batch(&[url]).await.pop().unwrap()
+    }
+
+    /// Check all `urls`, looking up to [`BATCH_SIZE`] mods per request and running up to
+    /// `concurrency` requests at the same time. `on_checked` is called for each finished check in
+    /// input order, and the returned checks are in input order as well.
+    pub async fn check_all<'a>(
+        &self,
+        urls: impl IntoIterator<Item = &'a str>,
+        mut on_checked: impl FnMut(&ModCheck),
+    ) -> Vec<ModCheck> {
+        let urls = urls.into_iter().collect::<Vec<_>>();
+        let mut batches = futures_util::stream::iter(urls.chunks(BATCH_SIZE))
+            .map(|batch| self.check_batch(batch))
+            .buffered(self.concurrency);
+
+        let mut results = vec![];
+        while let Some(checks) = batches.next().await {
+            for check in checks {
+                on_checked(&check);
+                results.push(check);
+            }
+        }
+        results
+    }
+}
+
+/// Find the mod referenced by `mod_ref` among the mods found by name and by id.
+fn find_mod<'m>(
+    url: &str,
+    mod_ref: &ModRef,
+    by_name: &'m Result<Vec<Mod>, Arc<BackendError>>,
+    by_id: &'m Result<Vec<Mod>, Arc<BackendError>>,
+) -> Result<&'m Mod, ModCheckError> {
+    let modio_error =
+        |error: &Arc<BackendError>| ModCheckError::modio(url.to_string(), Arc::clone(error));
+
+    let Some(mod_id) = mod_ref.mod_id else {
+        let mut by_name = by_name
+            .as_ref()
+            .map_err(modio_error)?
+            .iter()
+            .filter(|r#mod| mod_ref.name_id.as_ref() == Some(&r#mod.name_id));
+        let Some(r#mod) = by_name.next() else {
+            return Err(ModCheckError::ModNotFound { url: url.to_string() });
+        };
+        if by_name.next().is_some() {
+            return Err(ModCheckError::AmbiguousModUrl { url: url.to_string() });
+        }
+        return Ok(r#mod);
+    };
+
+    // The id from the URL fragment identifies the mod more precisely than its name, and still finds
+    // the mod after its name_id changed.
+    by_name.as_ref().map_err(modio_error)?;
+    let by_id = by_id.as_ref().map_err(modio_error)?.iter().find(|r#mod| r#mod.id == mod_id);
+    by_id.ok_or_else(|| ModCheckError::ModRemoved { url: url.to_string(), mod_id })
+}
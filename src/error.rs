@@ -0,0 +1,251 @@
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::modio::{User, MODIO_ERROR_REF_GAME_NOT_FOUND, MODIO_ERROR_REF_TOKEN_EXPIRED};
+
+#[derive(Debug, Error)]
+pub enum ModCheckError {
+    #[error("not a mod.io mod URL: <{url}>")]
+    InvalidModUrl { url: String },
+    #[error("mod not found: <{url}>")]
+    ModNotFound { url: String },
+    #[error("mod.io error for <{url}>: {error}")]
+    ModioError { url: String, error: Arc<BackendError> },
+    #[error("ambiguous mod.io URL: <{url}>")]
+    AmbiguousModUrl { url: String },
+    #[error("mod {mod_id} removed: <{url}>")]
+    ModRemoved { url: String, mod_id: u32 },
+    #[error("modfile {modfile_id} of mod {mod_id} removed: <{url}>")]
+    ModfileRemoved { url: String, mod_id: u32, modfile_id: u32 },
+    #[error("mod renamed: <{old_url}> is now <{new_url}>")]
+    Renamed { old_url: String, new_url: String },
+    #[error("mod hidden: <{url}>")]
+    Hidden { url: String },
+    #[error("mod deleted: <{url}>")]
+    Deleted { url: String },
+    #[error("mod pending moderation: <{url}>")]
+    PendingModeration { url: String },
+    #[error("mod of another game ({game}): <{url}>")]
+    GameMismatch { url: String, game: String },
+    #[error("access token expired or revoked: <{url}>")]
+    TokenExpired { url: String },
+    #[error("access token rejected ({message}): <{url}>")]
+    Unauthorized { url: String, message: String },
+    #[error("game not found on mod.io: <{url}>")]
+    GameNotFound { url: String },
+}
+
+/// Error looking up the game to check mods of.
+#[derive(Debug, Error)]
+pub enum GameError {
+    #[error("game `{game}` not found on mod.io")]
+    NotFound { game: String },
+    #[error("mod.io error looking up game `{game}`: {error}")]
+    ModioError { game: String, error: BackendError },
+}
+
+/// Error validating the access token before checking mods.
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("access token expired on {}", expires.format("%Y-%m-%d %H:%M UTC"))]
+    Expired { expires: DateTime<Utc> },
+    #[error("access token expired or revoked")]
+    TokenExpired,
+    #[error("access token rejected: {error}")]
+    Rejected { error: BackendError },
+    #[error("access token belongs to user {} (`{}`), not to user {user_id}", user.id, user.name_id)]
+    WrongUser { user_id: u64, user: User },
+    #[error("mod.io error validating access token: {error}")]
+    ModioError { error: BackendError },
+}
+
+impl From<BackendError> for AuthError {
+    fn from(error: BackendError) -> Self {
+        match (error.status(), error.api_error()) {
+            (_, Some(api)) if api.error_ref == MODIO_ERROR_REF_TOKEN_EXPIRED => {
+                AuthError::TokenExpired
+            }
+            (Some(401 | 403), _) => AuthError::Rejected { error },
+            _ => AuthError::ModioError { error },
+        }
+    }
+}
+
+/// Kind of outcome of checking a mod URL, without the details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutcomeKind {
+    Ok,
+    InvalidUrl,
+    NotFound,
+    ModioError,
+    Ambiguous,
+    ModRemoved,
+    ModfileRemoved,
+    Renamed,
+    Hidden,
+    Deleted,
+    PendingModeration,
+    GameMismatch,
+    TokenExpired,
+    Unauthorized,
+    GameNotFound,
+}
+
+impl OutcomeKind {
+    pub const ALL: [OutcomeKind; 15] = [
+        OutcomeKind::Ok,
+        OutcomeKind::InvalidUrl,
+        OutcomeKind::NotFound,
+        OutcomeKind::ModioError,
+        OutcomeKind::Ambiguous,
+        OutcomeKind::ModRemoved,
+        OutcomeKind::ModfileRemoved,
+        OutcomeKind::Renamed,
+        OutcomeKind::Hidden,
+        OutcomeKind::Deleted,
+        OutcomeKind::PendingModeration,
+        OutcomeKind::GameMismatch,
+        OutcomeKind::TokenExpired,
+        OutcomeKind::Unauthorized,
+        OutcomeKind::GameNotFound,
+    ];
+
+    /// Whether this is a problem with the checked mod, rather than mod.io failing to check it.
+    pub fn is_mod_problem(self) -> bool {
+        !matches!(
+            self,
+            OutcomeKind::Ok
+                | OutcomeKind::ModioError
+                | OutcomeKind::TokenExpired
+                | OutcomeKind::Unauthorized
+                | OutcomeKind::GameNotFound
+        )
+    }
+
+    /// Stable `snake_case` name, as used in reports.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OutcomeKind::Ok => "ok",
+            OutcomeKind::InvalidUrl => "invalid_url",
+            OutcomeKind::NotFound => "not_found",
+            OutcomeKind::ModioError => "modio_error",
+            OutcomeKind::Ambiguous => "ambiguous",
+            OutcomeKind::ModRemoved => "mod_removed",
+            OutcomeKind::ModfileRemoved => "modfile_removed",
+            OutcomeKind::Renamed => "renamed",
+            OutcomeKind::Hidden => "hidden",
+            OutcomeKind::Deleted => "deleted",
+            OutcomeKind::PendingModeration => "pending_moderation",
+            OutcomeKind::GameMismatch => "game_mismatch",
+            OutcomeKind::TokenExpired => "token_expired",
+            OutcomeKind::Unauthorized => "unauthorized",
+            OutcomeKind::GameNotFound => "game_not_found",
+        }
+    }
+}
+
+impl fmt::Display for OutcomeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for OutcomeKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl ModCheckError {
+    /// Classify a mod.io `error` checking `url` by the error reported by mod.io.
+    pub(crate) fn modio(url: String, error: Arc<BackendError>) -> Self {
+        match (error.status(), error.api_error()) {
+            (_, Some(api)) if api.error_ref == MODIO_ERROR_REF_TOKEN_EXPIRED => {
+                ModCheckError::TokenExpired { url }
+            }
+            (_, Some(api)) if api.error_ref == MODIO_ERROR_REF_GAME_NOT_FOUND => {
+                ModCheckError::GameNotFound { url }
+            }
+            (Some(401), api) => ModCheckError::Unauthorized {
+                url,
+                message: api.map_or_else(|| "unauthorized".to_string(), |api| api.to_string()),
+            },
+            _ => ModCheckError::ModioError { url, error },
+        }
+    }
+
+    pub fn kind(&self) -> OutcomeKind {
+        match self {
+            ModCheckError::InvalidModUrl { .. } => OutcomeKind::InvalidUrl,
+            ModCheckError::ModNotFound { .. } => OutcomeKind::NotFound,
+            ModCheckError::ModioError { .. } => OutcomeKind::ModioError,
+            ModCheckError::AmbiguousModUrl { .. } => OutcomeKind::Ambiguous,
+            ModCheckError::ModRemoved { .. } => OutcomeKind::ModRemoved,
+            ModCheckError::ModfileRemoved { .. } => OutcomeKind::ModfileRemoved,
+            ModCheckError::Renamed { .. } => OutcomeKind::Renamed,
+            ModCheckError::Hidden { .. } => OutcomeKind::Hidden,
+            ModCheckError::Deleted { .. } => OutcomeKind::Deleted,
+            ModCheckError::PendingModeration { .. } => OutcomeKind::PendingModeration,
+            ModCheckError::GameMismatch { .. } => OutcomeKind::GameMismatch,
+            ModCheckError::TokenExpired { .. } => OutcomeKind::TokenExpired,
+            ModCheckError::Unauthorized { .. } => OutcomeKind::Unauthorized,
+            ModCheckError::GameNotFound { .. } => OutcomeKind::GameNotFound,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match self {
+            ModCheckError::InvalidModUrl { url } => url,
+            ModCheckError::ModNotFound { url } => url,
+            ModCheckError::ModioError { url, .. } => url,
+            ModCheckError::AmbiguousModUrl { url } => url,
+            ModCheckError::ModRemoved { url, .. } => url,
+            ModCheckError::ModfileRemoved { url, .. } => url,
+            ModCheckError::Renamed { old_url, .. } => old_url,
+            ModCheckError::Hidden { url } => url,
+            ModCheckError::Deleted { url } => url,
+            ModCheckError::PendingModeration { url } => url,
+            ModCheckError::GameMismatch { url, .. } => url,
+            ModCheckError::TokenExpired { url } => url,
+            ModCheckError::Unauthorized { url, .. } => url,
+            ModCheckError::GameNotFound { url } => url,
+        }
+    }
+
+    pub fn status_code(&self) -> Option<u32> {
+        match self {
+            ModCheckError::InvalidModUrl { .. } => None,
+            ModCheckError::ModNotFound { .. } => Some(404),
+            ModCheckError::ModioError { error, .. } => error.status().map(u32::from),
+            ModCheckError::AmbiguousModUrl { .. } => None,
+            ModCheckError::ModRemoved { .. } => Some(404),
+            ModCheckError::ModfileRemoved { .. } => Some(404),
+            ModCheckError::Renamed { .. } => None,
+            ModCheckError::Hidden { .. } => None,
+            ModCheckError::Deleted { .. } => None,
+            ModCheckError::PendingModeration { .. } => None,
+            ModCheckError::GameMismatch { .. } => None,
+            ModCheckError::TokenExpired { .. } => Some(401),
+            ModCheckError::Unauthorized { .. } => Some(401),
+            ModCheckError::GameNotFound { .. } => Some(404),
+        }
+    }
+
+    /// Short explanation of errors reported by mod.io, to show next to the status code.
+    pub fn reason(&self) -> Option<String> {
+        match self {
+            ModCheckError::ModioError { error, .. } => error.api_error().map(ToString::to_string),
+            ModCheckError::TokenExpired { .. } => {
+                Some("access token expired or revoked".to_string())
+            }
+            ModCheckError::Unauthorized { message, .. } => Some(message.clone()),
+            ModCheckError::GameNotFound { .. } => Some("game not found".to_string()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Rewrite a mod list according to the results of checking it.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::checker::ModCheck;
+use crate::error::ModCheckError;
+use crate::mod_list::DuplicateMod;
+
+/// What to do with mods which are gone from mod.io (not found, removed, deleted or hidden).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnavailableMods {
+    /// Keep the line, prefixed with `# `.
+    #[default]
+    Comment,
+    /// Remove the line.
+    Drop,
+    /// Keep the line unchanged.
+    Keep,
+}
+
+/// Rewrite `mod_list` given the `checks` of its URLs and its `duplicates`: renamed mods are
+/// replaced by their current URL, repeated mods are only kept once, and unavailable mods are handled
+/// according to `unavailable`. Lines which weren't checked, and mods with other problems, are kept
+/// unchanged.
+pub fn fix_mod_list(
+    mod_list: &str,
+    checks: &[ModCheck],
+    duplicates: &[DuplicateMod],
+    unavailable: UnavailableMods,
+) -> String {
+    let checks = checks.iter().map(|check| (check.url.as_str(), check)).collect::<HashMap<_, _>>();
+    let duplicates = duplicates.iter().map(|duplicate| duplicate.line).collect::<HashSet<_>>();
+
+    let mut seen = HashSet::new();
+    let mut fixed = String::new();
+    for (i, line) in mod_list.lines().enumerate() {
+        if duplicates.contains(&(i + 1)) {
+            continue;
+        }
+        let url = line.trim();
+        let line = match checks.get(url).map(|check| &check.result) {
+            Some(Err(ModCheckError::Renamed { new_url, .. })) => new_url.clone(),
+            Some(Err(
+                ModCheckError::ModNotFound { .. }
+                | ModCheckError::ModRemoved { .. }
+                | ModCheckError::Deleted { .. }
+                | ModCheckError::Hidden { .. },
+            )) => match unavailable {
+                UnavailableMods::Comment => format!("# {url}"),
+                UnavailableMods::Drop => continue,
+                UnavailableMods::Keep => url.to_string(),
+            },
+            Some(_) => url.to_string(),
+            None => {
+                fixed.push_str(line);
+                fixed.push('\n');
+                continue;
+            }
+        };
+
+        if seen.insert(line.clone()) {
+            fixed.push_str(&line);
+            fixed.push('\n');
+        }
+    }
+    fixed
+}
@@ -0,0 +1,38 @@
+//! Check if mods referenced by a mod list still exist on mod.io.
+//!
+//! ```no_run
+//! use modio_modcheck::ModChecker;
+//!
+//! # async fn run() -> Result<(), modio_modcheck::BuildError> {
+//! let checker = ModChecker::builder().user_id(12345).token("oauth2-token").build()?;
+//! let check = checker.check("https://mod.io/g/drg/m/sandbox-utilities").await;
+//! if let Err(e) = &check.result {
+//!     eprintln!("{e}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod backend;
+mod checker;
+mod error;
+mod fix;
+mod mod_list;
+mod modio;
+pub mod report;
+mod token;
+mod url;
+
+pub use checker::{
+    BuildError, ModCheck, ModChecker, ModCheckerBuilder, BATCH_SIZE, DEFAULT_CONCURRENCY,
+};
+pub use error::{AuthError, GameError, ModCheckError, OutcomeKind};
+pub use fix::{fix_mod_list, UnavailableMods};
+pub use mod_list::{DuplicateMod, ModList, UnrecognisedLine};
+pub use modio::{
+    Game, Games, Mod, Modfile, Mods, User, MODIO_DRG_ID, MODIO_DRG_NAME_ID,
+    MODIO_ERROR_REF_GAME_NOT_FOUND, MODIO_ERROR_REF_TOKEN_EXPIRED, MODIO_PAGE_LIMIT,
+    MOD_STATUS_ACCEPTED, MOD_STATUS_DELETED, MOD_STATUS_NOT_ACCEPTED,
+};
+pub use token::{AccessToken, TokenError, TOKEN_EXPIRY_WARNING};
+pub use url::{re_mod, ModRef};
@@ -1,138 +1,313 @@
-use clap::Parser;
+use anyhow::Context;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeDelta, Utc};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use console::{Style, Term};
 use fs_err as fs;
 use indicatif::{ProgressBar, ProgressStyle};
+use modio_modcheck::backend::RetryPolicy;
+use modio_modcheck::report::{Report, ReportFormat};
+use modio_modcheck::{
+    fix_mod_list, AccessToken, AuthError, DuplicateMod, GameError, Mod, ModCheck, ModCheckError,
+    ModChecker, ModList, OutcomeKind, TokenError, UnavailableMods, UnrecognisedLine,
+    DEFAULT_CONCURRENCY, TOKEN_EXPIRY_WARNING,
+};
 use serde::Deserialize;
-use thiserror::Error;
 use tracing::*;
 
-use std::io::Write;
-use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::fmt;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::time::Duration;
 
 mod logging;
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    check: Option<CheckArgs>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check a mod list for hidden, renamed or deleted mods (the default)
+    Check(CheckArgs),
+    /// Check a mod list and write a corrected copy of it
+    Fix(FixArgs),
+}
+
+#[derive(Args)]
+struct CheckArgs {
     mod_list: PathBuf,
+    /// mod.io user id [default: the user the access token belongs to]
     #[arg(long = "id")]
-    user_id: u64,
+    user_id: Option<u64>,
+    /// File containing the OAuth2 access token, `-` to read it from stdin [default:
+    /// $MODIO_ACCESS_TOKEN, or the credentials file]
     #[arg(long = "access-token")]
-    oauth2_access_token: PathBuf,
+    oauth2_access_token: Option<PathBuf>,
+    /// mod.io API base URL, `{user_id}` is replaced by the user id [default:
+    /// https://u-{user_id}.modapi.io/v1]
+    #[arg(long = "api-base", env = "MODIO_API_BASE")]
+    api_base: Option<String>,
+    /// mod.io API base URL to look up the user of the access token without `--id` [default:
+    /// https://api.mod.io/v1]
+    #[arg(long = "global-api-base", env = "MODIO_GLOBAL_API_BASE")]
+    global_api_base: Option<String>,
+    /// mod.io game to check mods of, by name_id or id [default: drg]
+    #[arg(long)]
+    game: Option<String>,
+    /// Maximum number of requests sent at the same time
+    #[arg(long, default_value_t = DEFAULT_CONCURRENCY)]
+    concurrency: usize,
+    /// Maximum number of attempts for requests failing with transient errors
+    #[arg(
+        long,
+        default_value_t = RetryPolicy::default().max_attempts,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    max_attempts: u32,
+    /// Format of the report
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Where to write the report [default: errors.log, next to MOD_LIST if the current directory
+    /// isn't writable]
+    #[arg(long, short, visible_alias = "report")]
+    output: Option<PathBuf>,
+    /// Add the current date and time to the report file name, e.g. errors-2024-06-19T12-30-00.log
+    #[arg(long)]
+    timestamp: bool,
+    /// Comma-separated problems with mods which make the run fail [default: all problems]
+    #[arg(long, value_delimiter = ',', value_parser = outcome_kind_parser())]
+    fail_on: Option<Vec<OutcomeKind>>,
+    /// Fail if the mod list contains lines which aren't mod URLs
+    #[arg(long)]
+    strict: bool,
 }
 
-static RE_MOD: OnceLock<regex::Regex> = OnceLock::new();
-fn re_mod() -> &'static regex::Regex {
-    RE_MOD.get_or_init(|| regex::Regex::new("^https://mod.io/g/drg/m/(?P<name_id>[^/#]+)(:?#(?P<mod_id>\\d+)(:?/(?P<modfile_id>\\d+))?)?$").unwrap())
+fn outcome_kind_parser() -> impl TypedValueParser<Value = OutcomeKind> {
+    let problems = mod_problems();
+    PossibleValuesParser::new(problems.iter().map(|kind| kind.as_str()))
+        .map(|kind| *OutcomeKind::ALL.iter().find(|k| k.as_str() == kind).unwrap())
 }
 
-#[derive(Debug, Error)]
-enum ModCheckError {
-    #[error("mod not found: <{url}>")]
-    ModNotFound { url: String },
-    #[error("mod.io error for <{url}>: {error}")]
-    ModioError { url: String, error: reqwest::Error },
-    #[error("ambiguous mod.io URL: <{url}>")]
-    AmbiguousModUrl { url: String },
+/// Outcome kinds which are problems with mods, as selected by `--fail-on`.
+fn mod_problems() -> Vec<OutcomeKind> {
+    OutcomeKind::ALL.into_iter().filter(|kind| kind.is_mod_problem()).collect()
 }
 
-impl ModCheckError {
-    fn url(&self) -> &str {
-        match self {
-            ModCheckError::ModNotFound { url } => url,
-            ModCheckError::ModioError { url, .. } => url,
-            ModCheckError::AmbiguousModUrl { url } => url,
-        }
+/// Process exit codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Exit {
+    /// No mod has a problem counted by `--fail-on`.
+    Ok = 0,
+    /// Some mods have problems counted by `--fail-on`.
+    Problems = 1,
+    /// Invalid arguments, unreadable input files or, with `--strict`, unrecognised lines in the mod
+    /// list. Also used by clap for usage errors.
+    Usage = 2,
+    /// mod.io rejected the access token.
+    Auth = 3,
+    /// mod.io could not be reached or failed to respond properly.
+    Network = 4,
+}
+
+impl From<Exit> for ExitCode {
+    fn from(exit: Exit) -> Self {
+        ExitCode::from(exit as u8)
     }
+}
 
-    fn status_code(&self) -> Option<u32> {
-        match self {
-            ModCheckError::ModNotFound { .. } => Some(404),
-            ModCheckError::ModioError { error, .. } => {
-                error.status().map(|code| code.as_u16() as u32)
-            }
-            ModCheckError::AmbiguousModUrl { .. } => None,
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// One line per problem mod
+    Text,
+    /// One JSON record per checked URL
+    Json,
+    /// One CSV row per checked URL
+    Csv,
+    /// Markdown table of problem mods
+    Markdown,
+    /// JUnit XML test suite with one test case per checked URL
+    Junit,
+}
+
+impl From<Format> for ReportFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Text => ReportFormat::Text,
+            Format::Json => ReportFormat::Json,
+            Format::Csv => ReportFormat::Csv,
+            Format::Markdown => ReportFormat::Markdown,
+            Format::Junit => ReportFormat::Junit,
         }
     }
 }
 
-const MODIO_DRG_ID: u32 = 2475;
+#[derive(Args)]
+struct FixArgs {
+    #[command(flatten)]
+    check: CheckArgs,
+    /// Where to write the corrected mod list [default: <MOD_LIST>.fixed.txt]
+    #[arg(long)]
+    fixed: Option<PathBuf>,
+    /// What to do with mods which were not found, removed, deleted or hidden
+    #[arg(long, value_enum, default_value_t = Unavailable::Comment)]
+    unavailable: Unavailable,
+}
 
-#[derive(Debug, Deserialize)]
-struct Mods {
-    data: Vec<Mod>,
+#[derive(Clone, Copy, ValueEnum)]
+enum Unavailable {
+    /// Comment them out
+    Comment,
+    /// Remove them from the list
+    Drop,
+    /// Leave them in the list
+    Keep,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct Mod {
-    id: u32,
-    visible: u32,
-    profile_url: String,
+impl From<Unavailable> for UnavailableMods {
+    fn from(unavailable: Unavailable) -> Self {
+        match unavailable {
+            Unavailable::Comment => UnavailableMods::Comment,
+            Unavailable::Drop => UnavailableMods::Drop,
+            Unavailable::Keep => UnavailableMods::Keep,
+        }
+    }
 }
 
-fn fetch_mods_by_name(
-    client: &reqwest::blocking::Client,
-    user_id: u64,
-    token: &str,
-    url: &str,
-) -> Result<Mods, reqwest::Error> {
-    let name_id = re_mod().captures(url).unwrap().name("name_id").unwrap().as_str();
-    let url = format!(
-        "https://u-{user_id}.modapi.io/v1/games/{MODIO_DRG_ID}/mods?visible=1&name_id={name_id}"
-    );
-    let res = client.get(url).header("accept", "application/json").bearer_auth(token).send()?;
-    let mods: Mods = res.json()?;
-    Ok(mods)
-}
-
-fn check_url(
-    client: &reqwest::blocking::Client,
-    user_id: u64,
-    token: &str,
-    url: &str,
-) -> Result<Mod, ModCheckError> {
-    let mut mods = match fetch_mods_by_name(&client, user_id, token, url) {
-        Ok(mods) => mods,
-        Err(error) => {
-            debug!(?error, "request failed for <{url}>");
-            return Err(ModCheckError::ModioError { url: url.to_string(), error });
+#[tokio::main]
+async fn main() -> ExitCode {
+    logging::setup_logging();
+
+    match run(Cli::parse()).await {
+        Ok(exit) => exit.into(),
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            error_exit(&e).into()
         }
-    };
+    }
+}
 
-    let Some(r#mod) = mods.data.pop() else {
-        return Err(ModCheckError::ModNotFound { url: url.to_string() });
+async fn run(cli: Cli) -> anyhow::Result<Exit> {
+    let (args, checked) = match cli.command {
+        Some(Command::Check(cli)) => {
+            let checked = check(&cli).await?;
+            (cli, checked)
+        }
+        Some(Command::Fix(cli)) => {
+            let checked = check(&cli.check).await?;
+            let fixed = fix_mod_list(
+                &checked.mod_list,
+                &checked.checks,
+                &checked.duplicates,
+                cli.unavailable.into(),
+            );
+            let path = cli.fixed.unwrap_or_else(|| fixed_path(&cli.check.mod_list));
+            eprintln!("writing fixed mod list to `{}`", path.display());
+            fs::write(&path, fixed)?;
+            (cli.check, checked)
+        }
+        None => {
+            let cli = cli.check.expect("check arguments are required without a subcommand");
+            let checked = check(&cli).await?;
+            (cli, checked)
+        }
     };
 
-    if !mods.data.is_empty() {
-        return Err(ModCheckError::AmbiguousModUrl { url: url.to_string() });
+    Ok(exit_status(&checked, &args.fail_on.clone().unwrap_or_else(mod_problems), args.strict))
+}
+
+/// Exit status for an error aborting the run.
+fn error_exit(e: &anyhow::Error) -> Exit {
+    match e.downcast_ref::<AuthError>() {
+        Some(AuthError::ModioError { .. }) => return Exit::Network,
+        Some(_) => return Exit::Auth,
+        None => {}
+    }
+    match e.downcast_ref::<GameError>() {
+        Some(GameError::ModioError { error, .. }) if matches!(error.status(), Some(401 | 403)) => {
+            Exit::Auth
+        }
+        Some(GameError::ModioError { .. }) => Exit::Network,
+        _ => Exit::Usage,
+    }
+}
+
+/// Exit status for `checked`, only counting problems with mods of the `fail_on` kinds and
+/// unrecognised lines if `strict`. mod.io errors always count, and take precedence over problems
+/// with individual mods, as they may hide them.
+fn exit_status(checked: &Checked, fail_on: &[OutcomeKind], strict: bool) -> Exit {
+    let mut failures = checked
+        .checks
+        .iter()
+        .filter_map(|check| check.result.as_ref().err())
+        .filter_map(|e| match e {
+            ModCheckError::TokenExpired { .. } | ModCheckError::Unauthorized { .. } => {
+                Some(Exit::Auth)
+            }
+            ModCheckError::GameNotFound { .. } => Some(Exit::Usage),
+            ModCheckError::ModioError { .. } => Some(Exit::Network),
+            _ if fail_on.contains(&e.kind()) => Some(Exit::Problems),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if strict && !checked.unrecognised.is_empty() {
+        failures.push(Exit::Usage);
     }
 
-    Ok(r#mod)
+    [Exit::Auth, Exit::Network, Exit::Usage, Exit::Problems]
+        .into_iter()
+        .find(|exit| failures.contains(exit))
+        .unwrap_or(Exit::Ok)
 }
 
-fn main() -> anyhow::Result<()> {
-    logging::setup_logging();
+/// A checked mod list.
+struct Checked {
+    mod_list: String,
+    checks: Vec<ModCheck>,
+    duplicates: Vec<DuplicateMod>,
+    unrecognised: Vec<UnrecognisedLine>,
+}
 
-    let cli = Cli::parse();
-    assert!(cli.mod_list.exists(), "`{}` does not exist", cli.mod_list.display());
-    assert!(
-        cli.oauth2_access_token.exists(),
-        "`{}` does not exist",
-        cli.oauth2_access_token.display()
-    );
-    let token = fs::read_to_string(&cli.oauth2_access_token)?;
-    let token = token.trim();
+/// Check the mods of `cli.mod_list` and write the report.
+async fn check(cli: &CheckArgs) -> anyhow::Result<Checked> {
+    anyhow::ensure!(cli.mod_list.exists(), "`{}` does not exist", cli.mod_list.display());
+    let (token, user_id) = read_credentials(cli)?;
 
-    let mod_list = fs::read_to_string(&cli.mod_list)?;
-    let mut mod_list = mod_list.lines().filter(|url| re_mod().is_match(url)).collect::<Vec<_>>();
-    mod_list.dedup();
+    let mod_list_text = fs::read_to_string(&cli.mod_list)?;
+    let ModList { urls: mod_list, duplicates, unrecognised } = ModList::parse(&mod_list_text);
     debug!("mods_list: {:#?}", mod_list);
 
-    let mut errors = vec![];
+    let cyan_bold = Style::new().cyan().bold();
+    let blue = Style::new().blue();
+    let red_bold = Style::new().red().bold();
+    let yellow_bold = Style::new().yellow().bold();
+    let magenta_bold = Style::new().magenta().bold();
 
-    let client = reqwest::blocking::Client::new();
+    if let (Some(expires), Some(expires_in)) = (token.expires, token.expires_in(Utc::now())) {
+        if expires_in <= TimeDelta::zero() {
+            return Err(AuthError::Expired { expires }.into());
+        }
+        if expires_in < TOKEN_EXPIRY_WARNING {
+            eprintln!(
+                "{:>12} access token expires {}, in {}",
+                yellow_bold.apply_to("WARN"),
+                expires.format("%Y-%m-%d %H:%M UTC"),
+                format_days(expires_in),
+            );
+        }
+    }
+    for UnrecognisedLine { line, text } in &unrecognised {
+        eprintln!("{:>12} line {line}: {text}", yellow_bold.apply_to("UNRECOGNISED"));
+    }
+    for duplicate @ DuplicateMod { line, url, .. } in &duplicates {
+        eprintln!("{:>12} line {line}: {url} ({duplicate})", yellow_bold.apply_to("DUPLICATE"));
+    }
 
     let pb = ProgressBar::new(mod_list.len() as u64);
     pb.set_style(
@@ -146,75 +321,284 @@ fn main() -> anyhow::Result<()> {
     pb.set_prefix("Checking");
     pb.enable_steady_tick(Duration::from_millis(100));
 
-    let cyan_bold = Style::new().cyan().bold();
-    let blue = Style::new().blue();
-    let red_bold = Style::new().red().bold();
-    let yellow_bold = Style::new().yellow().bold();
+    let mut builder = ModChecker::builder()
+        .token(token.token)
+        .concurrency(cli.concurrency)
+        .retry_policy(RetryPolicy { max_attempts: cli.max_attempts, ..RetryPolicy::default() })
+        .on_rate_limit({
+            let pb = pb.clone();
+            move |wait| {
+                let line = format!(
+                    "{:>12} waiting {} to not trigger mod.io rate limit",
+                    cyan_bold.apply_to("INFO"),
+                    blue.apply_to(format_wait(wait))
+                );
+                print_line(&pb, &line);
+            }
+        });
+    if let Some(user_id) = user_id {
+        builder = builder.user_id(user_id);
+    }
+    if let Some(api_base) = &cli.api_base {
+        builder = builder.api_base(api_base.clone());
+    }
+    if let Some(global_api_base) = &cli.global_api_base {
+        builder = builder.global_api_base(global_api_base.clone());
+    }
+    let mut checker = builder.clone().build()?;
+    let user = checker.verify_token(user_id).await.inspect_err(|_| pb.finish_and_clear())?;
+    debug!(?user, "access token valid");
+    if user_id.is_none() {
+        // Switch to the per-user API host of the user the token belongs to.
+        checker = builder.user_id(user.id).build()?;
+    }
+    if let Some(game) = &cli.game {
+        let game = checker.find_game(game).await.inspect_err(|_| pb.finish_and_clear())?;
+        debug!(?game, "checking mods of game");
+        checker = checker.with_game(game);
+    }
 
-    const CHUNK_SIZE: usize = 30;
-    const SLEEP_SECS: u64 = 60;
-    for chunk in mod_list.chunks(CHUNK_SIZE) {
-        for url in chunk {
-            debug!("checking {url}...");
-            match check_url(&client, cli.user_id, token, url) {
+    let checks = checker
+        .check_all(mod_list.iter().copied(), |ModCheck { url, result, .. }| {
+            match result {
                 Ok(Mod { profile_url, .. }) => {
-                    debug!(profile_url, "OK");
+                    debug!(url, profile_url, "OK");
+                }
+                Err(ModCheckError::Renamed { old_url, new_url }) => {
+                    debug!(old_url, new_url, "RENAMED");
+
+                    let line = format!(
+                        "{:>12} {:>3} {} -> {}",
+                        yellow_bold.apply_to("RENAMED"),
+                        yellow_bold.apply_to("-"),
+                        old_url,
+                        new_url,
+                    );
+                    print_line(&pb, &line);
                 }
                 Err(e) => {
                     debug!(?e, "INVALID");
 
+                    let label = match e {
+                        ModCheckError::Hidden { .. } => magenta_bold.apply_to("HIDDEN"),
+                        ModCheckError::Deleted { .. } => red_bold.apply_to("DELETED"),
+                        ModCheckError::PendingModeration { .. } => yellow_bold.apply_to("PENDING"),
+                        _ => red_bold.apply_to("ERROR"),
+                    };
                     let status = e
                         .status_code()
                         .map(|code| code.to_string())
                         .unwrap_or_else(|| "-".to_string());
                     let url = e.url();
 
-                    let line = format!(
-                        "{:>12} {:>3} {}",
-                        red_bold.apply_to("ERROR"),
-                        yellow_bold.apply_to(status),
-                        url,
-                    );
-                    pb.println(line);
-
-                    errors.push(e);
+                    let mut line =
+                        format!("{:>12} {:>3} {}", label, yellow_bold.apply_to(status), url);
+                    if let Some(reason) = e.reason() {
+                        line.push_str(&format!(" ({reason})"));
+                    }
+                    print_line(&pb, &line);
                 }
             }
 
             pb.inc(1);
-        }
+        })
+        .await;
+    pb.finish_and_clear();
 
-        debug!("sleeping 60 seconds to avoid rate-limit");
+    let (path, file) = create_report(cli)?;
+    eprintln!("check completed, writing log to `{}`", path.display());
 
-        if chunk.len() == CHUNK_SIZE {
-            let line = format!(
-                "{:>12} waiting {} to not trigger mod.io rate limit",
-                cyan_bold.apply_to("INFO"),
-                blue.apply_to("60 seconds")
-            );
-            pb.println(line);
-            std::thread::sleep(Duration::from_secs(SLEEP_SECS));
+    let mut out = BufWriter::new(file);
+    let report = Report {
+        checks: &checks,
+        duplicates: &duplicates,
+        unrecognised: &unrecognised,
+        token_expires: token.expires,
+    };
+    report.write(cli.format.into(), &mut out)?;
+    out.flush()?;
+
+    Ok(Checked { mod_list: mod_list_text, checks, duplicates, unrecognised })
+}
+
+/// Environment variable to pass the access token in, instead of `--access-token`.
+const ACCESS_TOKEN_ENV: &str = "MODIO_ACCESS_TOKEN";
+
+/// Contents of the credentials file.
+#[derive(Deserialize)]
+struct Credentials {
+    access_token: String,
+    user_id: Option<u64>,
+    expires: Option<Expires>,
+}
+
+/// When the access token of the credentials file expires.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Expires {
+    Date(toml::value::Datetime),
+    Timestamp(i64),
+    Text(String),
+}
+
+impl fmt::Display for Expires {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expires::Date(date) => date.fmt(f),
+            Expires::Timestamp(timestamp) => timestamp.fmt(f),
+            Expires::Text(text) => f.write_str(text),
         }
     }
-    pb.finish_and_clear();
+}
 
-    let error_log = PathBuf::from("errors.log");
+/// `date_time` as a point in time, taking a date without a time as midnight and a date and time without
+/// an offset as UTC. `None` for a time without a date.
+fn utc_datetime(date_time: &toml::value::Datetime) -> Option<DateTime<Utc>> {
+    let toml::value::Date { year, month, day } = date_time.date?;
+    let date = NaiveDate::from_ymd_opt(year.into(), month.into(), day.into())?;
+    let time = match date_time.time {
+        Some(toml::value::Time { hour, minute, second, nanosecond }) => {
+            NaiveTime::from_hms_nano_opt(hour.into(), minute.into(), second.into(), nanosecond)?
+        }
+        None => NaiveTime::MIN,
+    };
+    let offset = match date_time.offset {
+        Some(toml::value::Offset::Custom { minutes }) => TimeDelta::minutes(minutes.into()),
+        Some(toml::value::Offset::Z) | None => TimeDelta::zero(),
+    };
+    Some(date.and_time(time).and_utc() - offset)
+}
+
+/// `modio-modcheck/credentials.toml` in the user's config directory.
+fn credentials_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("modio-modcheck").join("credentials.toml"))
+}
 
-    eprintln!("check completed, writing log to `{}`", error_log.display());
+/// Read the access token from `--access-token`, `$MODIO_ACCESS_TOKEN` or the credentials file,
+/// whichever is given first, along with the user id from `--id` or the credentials file.
+fn read_credentials(cli: &CheckArgs) -> anyhow::Result<(AccessToken, Option<u64>)> {
+    match &cli.oauth2_access_token {
+        Some(path) if path == Path::new("-") => {
+            let token = AccessToken::parse(&io::read_to_string(io::stdin())?)?;
+            return Ok((token, cli.user_id));
+        }
+        Some(path) => {
+            anyhow::ensure!(path.exists(), "`{}` does not exist", path.display());
+            return Ok((AccessToken::parse(&fs::read_to_string(path)?)?, cli.user_id));
+        }
+        None => {}
+    }
+    if let Some(token) = std::env::var(ACCESS_TOKEN_ENV).ok().filter(|t| !t.trim().is_empty()) {
+        return Ok((AccessToken::new(token), cli.user_id));
+    }
 
-    let mut out = fs::File::create(&error_log)?;
-    for e in &errors {
-        match e {
-            ModCheckError::ModNotFound { url } => writeln!(&mut out, "ERROR {:<10} {url}", 404)?,
-            ModCheckError::ModioError { url, error } => match error.status() {
-                Some(code) => writeln!(&mut out, "ERROR {code:<10} {url}")?,
-                None => writeln!(&mut out, "ERROR {:<10} {url}", "---")?,
-            },
-            ModCheckError::AmbiguousModUrl { url } => {
-                writeln!(&mut out, "ERROR {:<10} {url}", "ambiguous")?
+    let path = credentials_path().context("no access token given and no config directory found")?;
+    anyhow::ensure!(
+        path.exists(),
+        "no access token given, pass `--access-token`, set `{ACCESS_TOKEN_ENV}` or create `{}`",
+        path.display()
+    );
+    let Credentials { access_token, user_id, expires } =
+        toml::from_str(&fs::read_to_string(&path)?)
+            .with_context(|| format!("invalid credentials file `{}`", path.display()))?;
+    let mut token = AccessToken::new(access_token);
+    if let Some(expires) = expires {
+        let invalid = || TokenError::InvalidExpiry { value: expires.to_string() };
+        token = match &expires {
+            Expires::Date(date) => {
+                utc_datetime(date).map(|expires| token.expiring_at(expires)).ok_or_else(invalid)
             }
+            Expires::Timestamp(timestamp) => DateTime::from_timestamp(*timestamp, 0)
+                .map(|expires| token.expiring_at(expires))
+                .ok_or_else(invalid),
+            Expires::Text(text) => token.with_expiry(text),
         }
+        .with_context(|| format!("invalid credentials file `{}`", path.display()))?;
+    }
+    Ok((token, cli.user_id.or(user_id)))
+}
+
+/// Create the report file at `--output`, or at `errors.log` in the current directory falling back
+/// to next to the mod list if the current directory isn't writable.
+fn create_report(cli: &CheckArgs) -> anyhow::Result<(PathBuf, fs::File)> {
+    let mut path = cli.output.clone().unwrap_or_else(|| PathBuf::from("errors.log"));
+    if cli.timestamp {
+        path = timestamped_path(&path, Local::now());
     }
 
-    Ok(())
+    match create_keeping_previous(&path) {
+        Err(e) if cli.output.is_none() && e.kind() == io::ErrorKind::PermissionDenied => {
+            let mod_list = fs::canonicalize(&cli.mod_list)?;
+            let path = mod_list.parent().context("mod list has no parent directory")?.join(&path);
+            debug!(?e, "current directory isn't writable, writing report to `{}`", path.display());
+            let file = create_keeping_previous(&path)?;
+            Ok((path, file))
+        }
+        file => Ok((path, file?)),
+    }
+}
+
+/// Create `path`, first moving an existing file at `path` out of the way instead of overwriting it,
+/// to `errors.previous.log` or, if that exists as well, `errors.previous-1.log`, `-2`, ...
+fn create_keeping_previous(path: &Path) -> io::Result<fs::File> {
+    if path.exists() {
+        let previous = (0..)
+            .map(|n| match n {
+                0 => with_stem_suffix(path, ".previous"),
+                n => with_stem_suffix(path, &format!(".previous-{n}")),
+            })
+            .find(|previous| !previous.exists())
+            .unwrap();
+        fs::rename(path, &previous)?;
+        eprintln!("keeping previous report as `{}`", previous.display());
+    }
+    fs::File::create(path)
+}
+
+/// `errors.log` -> `errors-2024-06-19T12-30-00.log`
+fn timestamped_path(path: &Path, now: DateTime<Local>) -> PathBuf {
+    with_stem_suffix(path, &now.format("-%Y-%m-%dT%H-%M-%S").to_string())
+}
+
+/// `errors.log` -> `errors{suffix}.log`
+fn with_stem_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(extension) => {
+            path.with_file_name(format!("{stem}{suffix}.{}", extension.to_string_lossy()))
+        }
+        None => path.with_file_name(format!("{stem}{suffix}")),
+    }
+}
+
+/// `mods.txt` -> `mods.fixed.txt`
+fn fixed_path(mod_list: &Path) -> PathBuf {
+    let stem = mod_list.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = mod_list.extension().map_or("txt".into(), |ext| ext.to_string_lossy());
+    mod_list.with_file_name(format!("{stem}.fixed.{extension}"))
+}
+
+/// Print `line` to stderr above the progress bar. `ProgressBar::println` would drop it when the bar is
+/// hidden because stderr isn't a terminal.
+fn print_line(pb: &ProgressBar, line: &str) {
+    pb.suspend(|| eprintln!("{line}"));
+}
+
+/// Format a rate limit wait rounded up to whole seconds, e.g. `42 seconds`.
+fn format_wait(wait: Duration) -> String {
+    let secs = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    if secs == 1 {
+        "1 second".to_string()
+    } else {
+        format!("{secs} seconds")
+    }
+}
+
+/// Format the time left until the access token expires, in whole days.
+fn format_days(left: TimeDelta) -> String {
+    match left.num_days() {
+        0 => "less than a day".to_string(),
+        1 => "1 day".to_string(),
+        days => format!("{days} days"),
+    }
 }
@@ -0,0 +1,81 @@
+//! Reading the mod URLs out of a mod list.
+
+use std::fmt;
+
+use crate::url::ModRef;
+
+/// A line of a mod list which isn't a mod URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnrecognisedLine {
+    /// 1-based line number.
+    pub line: usize,
+    pub text: String,
+}
+
+/// A reference to a mod which was already referenced by an earlier line of a mod list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateMod {
+    /// 1-based line number.
+    pub line: usize,
+    pub url: String,
+    /// 1-based line number of the first reference to the mod, which is the one checked.
+    pub first_line: usize,
+    pub first_url: String,
+    /// Whether the references pin different modfiles of the mod.
+    pub different_modfile: bool,
+}
+
+impl fmt::Display for DuplicateMod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.different_modfile {
+            write!(f, "different modfile of the same mod as line {}", self.first_line)
+        } else {
+            write!(f, "same mod as line {}", self.first_line)
+        }
+    }
+}
+
+/// The mod URLs of a mod list, and the lines which aren't mod URLs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModList<'a> {
+    /// Mod references in list order, trimmed and with each mod only referenced once.
+    pub urls: Vec<&'a str>,
+    pub duplicates: Vec<DuplicateMod>,
+    pub unrecognised: Vec<UnrecognisedLine>,
+}
+
+impl<'a> ModList<'a> {
+    /// Split `text` into mod references as accepted by [`ModRef::parse`] and unrecognised lines.
+    /// Blank lines and `#` comments, as written by [`fix_mod_list`](crate::fix_mod_list), are
+    /// ignored. Only the first reference to each mod is kept, see [`ModRef::same_mod`].
+    pub fn parse(text: &'a str) -> Self {
+        let mut mod_list = ModList::default();
+        let mut refs: Vec<(usize, &str, ModRef)> = vec![];
+        for (i, line) in text.lines().enumerate() {
+            let url = line.trim();
+            let Some(mod_ref) = ModRef::parse(url) else {
+                if !url.is_empty() && !url.starts_with('#') {
+                    mod_list
+                        .unrecognised
+                        .push(UnrecognisedLine { line: i + 1, text: line.to_string() });
+                }
+                continue;
+            };
+
+            match refs.iter().find(|(_, _, first)| first.same_mod(&mod_ref)) {
+                Some((first_line, first_url, first)) => mod_list.duplicates.push(DuplicateMod {
+                    line: i + 1,
+                    url: url.to_string(),
+                    first_line: *first_line,
+                    first_url: first_url.to_string(),
+                    different_modfile: first.modfile_id != mod_ref.modfile_id,
+                }),
+                None => {
+                    mod_list.urls.push(url);
+                    refs.push((i + 1, url, mod_ref));
+                }
+            }
+        }
+        mod_list
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+/// mod.io game id of Deep Rock Galactic.
+pub const MODIO_DRG_ID: u32 = 2475;
+
+/// mod.io game `name_id` of Deep Rock Galactic, as used in mod URLs.
+pub const MODIO_DRG_NAME_ID: &str = "drg";
+
+/// [`ApiError::error_ref`](crate::backend::ApiError::error_ref) of requests with an expired or
+/// revoked OAuth2 access token.
+pub const MODIO_ERROR_REF_TOKEN_EXPIRED: u32 = 11005;
+/// [`ApiError::error_ref`](crate::backend::ApiError::error_ref) of requests for a game which
+/// doesn't exist.
+pub const MODIO_ERROR_REF_GAME_NOT_FOUND: u32 = 14001;
+
+/// Maximum number of results mod.io returns per page.
+pub const MODIO_PAGE_LIMIT: u32 = 100;
+
+/// A page of mods, see <https://docs.mod.io/restapi/docs/pagination>.
+#[derive(Debug, Deserialize)]
+pub struct Mods {
+    pub data: Vec<Mod>,
+    pub result_count: u32,
+    pub result_offset: u32,
+    pub result_total: u32,
+}
+
+/// [`Mod::status`] of mods not yet accepted by moderators.
+pub const MOD_STATUS_NOT_ACCEPTED: u32 = 0;
+/// [`Mod::status`] of live mods.
+pub const MOD_STATUS_ACCEPTED: u32 = 1;
+/// [`Mod::status`] of deleted mods.
+pub const MOD_STATUS_DELETED: u32 = 3;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Mod {
+    pub id: u32,
+    pub name_id: String,
+    pub status: u32,
+    pub visible: u32,
+    pub profile_url: String,
+}
+
+/// A page of games, see <https://docs.mod.io/restapi/docs/get-games>.
+#[derive(Debug, Deserialize)]
+pub struct Games {
+    pub data: Vec<Game>,
+}
+
+/// The mod.io user an access token belongs to, as returned by `/me`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct User {
+    pub id: u64,
+    pub name_id: String,
+    pub username: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Game {
+    pub id: u32,
+    /// As used in URLs, e.g. `drg` in `https://mod.io/g/drg`.
+    pub name_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Modfile {
+    pub id: u32,
+    pub mod_id: u32,
+}
@@ -0,0 +1,53 @@
+//! Reports of check results, in various formats.
+
+use std::io::{self, Write};
+
+use chrono::{DateTime, Utc};
+
+use crate::checker::ModCheck;
+use crate::mod_list::{DuplicateMod, UnrecognisedLine};
+
+mod csv;
+mod json;
+mod junit;
+mod markdown;
+mod text;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// One line per problem mod, the `errors.log` format.
+    #[default]
+    Text,
+    /// One record per checked URL.
+    Json,
+    /// One row per checked URL.
+    Csv,
+    /// Table of problem mods with links, for pasting into chats or issues.
+    Markdown,
+    /// JUnit XML with one test case per checked URL, for CI dashboards.
+    Junit,
+}
+
+/// Results of checking a mod list.
+#[derive(Debug, Clone, Copy)]
+pub struct Report<'a> {
+    pub checks: &'a [ModCheck],
+    /// Repeated references to mods of the mod list, which weren't checked again.
+    pub duplicates: &'a [DuplicateMod],
+    /// Lines of the mod list which weren't checked as they aren't mod URLs.
+    pub unrecognised: &'a [UnrecognisedLine],
+    /// When the access token used for checking expires, if known.
+    pub token_expires: Option<DateTime<Utc>>,
+}
+
+impl Report<'_> {
+    pub fn write(&self, format: ReportFormat, out: &mut impl Write) -> io::Result<()> {
+        match format {
+            ReportFormat::Text => text::write(self, out),
+            ReportFormat::Json => json::write(self, out),
+            ReportFormat::Csv => csv::write(self, out),
+            ReportFormat::Markdown => markdown::write(self, out),
+            ReportFormat::Junit => junit::write(self, out),
+        }
+    }
+}
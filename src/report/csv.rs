@@ -0,0 +1,90 @@
+use std::io::{self, Write};
+
+use crate::error::ModCheckError;
+use crate::mod_list::{DuplicateMod, UnrecognisedLine};
+use crate::report::Report;
+
+const HEADER: [&str; 9] = [
+    "url",
+    "name_id",
+    "mod_id",
+    "modfile_id",
+    "outcome",
+    "status",
+    "message",
+    "new_url",
+    "profile_url",
+];
+
+pub(super) fn write(report: &Report<'_>, out: &mut impl Write) -> io::Result<()> {
+    write_row(out, HEADER.map(String::from))?;
+    for check in report.checks {
+        let error = check.result.as_ref().err();
+        let mod_ref = check.mod_ref.as_ref();
+        write_row(
+            out,
+            [
+                check.url.clone(),
+                mod_ref.and_then(|mod_ref| mod_ref.name_id.clone()).unwrap_or_default(),
+                optional(mod_ref.and_then(|mod_ref| mod_ref.mod_id)),
+                optional(mod_ref.and_then(|mod_ref| mod_ref.modfile_id)),
+                check.kind().to_string(),
+                optional(error.and_then(ModCheckError::status_code)),
+                error.map(ToString::to_string).unwrap_or_default(),
+                match error {
+                    Some(ModCheckError::Renamed { new_url, .. }) => new_url.clone(),
+                    _ => String::new(),
+                },
+                check.found.as_ref().map(|r#mod| r#mod.profile_url.clone()).unwrap_or_default(),
+            ],
+        )?;
+    }
+    for duplicate @ DuplicateMod { line, url, .. } in report.duplicates {
+        write_unchecked_row(out, url, "duplicate", format!("line {line}: {duplicate}"))?;
+    }
+    for UnrecognisedLine { line, text } in report.unrecognised {
+        let message = format!("unrecognised input on line {line}");
+        write_unchecked_row(out, text, "unrecognised", message)?;
+    }
+    Ok(())
+}
+
+/// Write a row for a line of the mod list which wasn't checked.
+fn write_unchecked_row(
+    out: &mut impl Write,
+    text: &str,
+    outcome: &str,
+    message: String,
+) -> io::Result<()> {
+    let [name_id, mod_id, modfile_id, status, new_url, profile_url] = Default::default();
+    write_row(
+        out,
+        [
+            text.to_string(),
+            name_id,
+            mod_id,
+            modfile_id,
+            outcome.to_string(),
+            status,
+            message,
+            new_url,
+            profile_url,
+        ],
+    )
+}
+
+fn optional(value: Option<u32>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Write a row of fields as per RFC 4180, quoting fields only where needed.
+fn write_row<const N: usize>(out: &mut impl Write, fields: [String; N]) -> io::Result<()> {
+    let fields = fields.map(|field| {
+        if field.contains([',', '"', '\r', '\n']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field
+        }
+    });
+    write!(out, "{}\r\n", fields.join(","))
+}
@@ -0,0 +1,98 @@
+use std::io::{self, Write};
+
+use chrono::SecondsFormat;
+use serde::Serialize;
+
+use crate::error::{ModCheckError, OutcomeKind};
+use crate::modio::Mod;
+use crate::report::Report;
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    /// RFC 3339 date the access token expires, if known.
+    token_expires: Option<String>,
+    checks: Vec<Record<'a>>,
+    duplicates: Vec<Duplicate<'a>>,
+    unrecognised: Vec<Unrecognised<'a>>,
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    url: &'a str,
+    name_id: Option<&'a str>,
+    mod_id: Option<u32>,
+    modfile_id: Option<u32>,
+    outcome: OutcomeKind,
+    /// HTTP status associated with the outcome, if any.
+    status: Option<u32>,
+    /// Human readable description of the problem, if any.
+    message: Option<String>,
+    /// Current URL of renamed mods.
+    new_url: Option<&'a str>,
+    /// The mod returned by mod.io.
+    #[serde(rename = "mod")]
+    r#mod: Option<&'a Mod>,
+}
+
+#[derive(Serialize)]
+struct Duplicate<'a> {
+    line: usize,
+    url: &'a str,
+    first_line: usize,
+    first_url: &'a str,
+    different_modfile: bool,
+}
+
+#[derive(Serialize)]
+struct Unrecognised<'a> {
+    line: usize,
+    text: &'a str,
+}
+
+pub(super) fn write(report: &Report<'_>, out: &mut impl Write) -> io::Result<()> {
+    let checks = report
+        .checks
+        .iter()
+        .map(|check| {
+            let error = check.result.as_ref().err();
+            Record {
+                url: &check.url,
+                name_id: check.mod_ref.as_ref().and_then(|mod_ref| mod_ref.name_id.as_deref()),
+                mod_id: check.mod_ref.as_ref().and_then(|mod_ref| mod_ref.mod_id),
+                modfile_id: check.mod_ref.as_ref().and_then(|mod_ref| mod_ref.modfile_id),
+                outcome: check.kind(),
+                status: error.and_then(ModCheckError::status_code),
+                message: error.map(ToString::to_string),
+                new_url: match error {
+                    Some(ModCheckError::Renamed { new_url, .. }) => Some(new_url),
+                    _ => None,
+                },
+                r#mod: check.found.as_ref(),
+            }
+        })
+        .collect();
+
+    let unrecognised = report
+        .unrecognised
+        .iter()
+        .map(|unrecognised| Unrecognised { line: unrecognised.line, text: &unrecognised.text })
+        .collect();
+
+    let duplicates = report
+        .duplicates
+        .iter()
+        .map(|duplicate| Duplicate {
+            line: duplicate.line,
+            url: &duplicate.url,
+            first_line: duplicate.first_line,
+            first_url: &duplicate.first_url,
+            different_modfile: duplicate.different_modfile,
+        })
+        .collect();
+
+    let token_expires =
+        report.token_expires.map(|expires| expires.to_rfc3339_opts(SecondsFormat::Secs, true));
+    let report = JsonReport { token_expires, checks, duplicates, unrecognised };
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)
+}
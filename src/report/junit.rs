@@ -0,0 +1,102 @@
+use std::io::{self, Write};
+
+use chrono::SecondsFormat;
+
+use crate::mod_list::UnrecognisedLine;
+use crate::report::Report;
+
+pub(super) fn write(report: &Report<'_>, out: &mut impl Write) -> io::Result<()> {
+    let tests = report.checks.len();
+    let failures = report.checks.iter().filter(|check| check.result.is_err()).count();
+    let skipped = report.duplicates.len() + report.unrecognised.len();
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites name="modio-modcheck" tests="{}" failures="{failures}" skipped="{skipped}">"#,
+        tests + skipped
+    )?;
+    writeln!(
+        out,
+        r#"  <testsuite name="mod list" tests="{tests}" failures="{failures}" errors="0">"#
+    )?;
+    if let Some(expires) = report.token_expires {
+        writeln!(out, "    <properties>")?;
+        writeln!(
+            out,
+            r#"      <property name="token_expires" value="{}"/>"#,
+            expires.to_rfc3339_opts(SecondsFormat::Secs, true)
+        )?;
+        writeln!(out, "    </properties>")?;
+    }
+    for check in report.checks {
+        let name = escape(&check.url);
+        let Err(e) = &check.result else {
+            writeln!(out, r#"    <testcase classname="modio-modcheck" name="{name}"/>"#)?;
+            continue;
+        };
+        let status = e.status_code().map_or("-".to_string(), |code| code.to_string());
+        let message = escape(&e.to_string());
+        writeln!(out, r#"    <testcase classname="modio-modcheck" name="{name}">"#)?;
+        writeln!(
+            out,
+            r#"      <failure type="{}" message="{message}">status: {status}&#10;{message}</failure>"#,
+            e.kind(),
+        )?;
+        writeln!(out, "    </testcase>")?;
+    }
+    writeln!(out, "  </testsuite>")?;
+
+    let duplicates = report.duplicates.iter().map(|duplicate| {
+        (format!("line {}: {}", duplicate.line, duplicate.url), duplicate.to_string())
+    });
+    write_skipped_suite(out, "duplicates", duplicates)?;
+    let unrecognised = report.unrecognised.iter().map(|UnrecognisedLine { line, text }| {
+        (format!("line {line}: {text}"), "not a mod URL".to_string())
+    });
+    write_skipped_suite(out, "unrecognised input", unrecognised)?;
+    writeln!(out, "</testsuites>")
+}
+
+/// Write a test suite of skipped test cases, given by their names and skip messages. Nothing is
+/// written if there are none.
+fn write_skipped_suite(
+    out: &mut impl Write,
+    suite: &str,
+    cases: impl ExactSizeIterator<Item = (String, String)>,
+) -> io::Result<()> {
+    let skipped = cases.len();
+    if skipped == 0 {
+        return Ok(());
+    }
+    writeln!(
+        out,
+        r#"  <testsuite name="{suite}" tests="{skipped}" failures="0" errors="0" skipped="{skipped}">"#
+    )?;
+    for (name, message) in cases {
+        let (name, message) = (escape(&name), escape(&message));
+        writeln!(out, r#"    <testcase classname="modio-modcheck" name="{name}">"#)?;
+        writeln!(out, r#"      <skipped message="{message}"/>"#)?;
+        writeln!(out, "    </testcase>")?;
+    }
+    writeln!(out, "  </testsuite>")
+}
+
+/// Escape `text` for use in XML attributes and text content.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            // Other control characters aren't allowed in XML 1.0 at all.
+            c if c.is_control() && c != '\t' && c != '\r' => escaped.push('\u{FFFD}'),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
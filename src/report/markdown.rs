@@ -0,0 +1,103 @@
+use std::io::{self, Write};
+
+use crate::error::ModCheckError;
+use crate::mod_list::{DuplicateMod, UnrecognisedLine};
+use crate::modio::Mod;
+use crate::report::Report;
+use crate::url::ModRef;
+
+pub(super) fn write(report: &Report<'_>, out: &mut impl Write) -> io::Result<()> {
+    let problems = report.checks.iter().filter(|check| check.result.is_err()).collect::<Vec<_>>();
+
+    writeln!(out, "## mod.io modcheck report")?;
+    writeln!(out)?;
+    if let Some(expires) = report.token_expires {
+        writeln!(out, "Access token expires {}.", expires.format("%Y-%m-%d %H:%M UTC"))?;
+        writeln!(out)?;
+    }
+    writeln!(out, "{} of {} mods have problems.", problems.len(), report.checks.len())?;
+    if !problems.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "| Mod | Problem | Status | Details |")?;
+        writeln!(out, "| --- | --- | --- | --- |")?;
+    }
+    for check in problems {
+        let Err(e) = &check.result else { unreachable!() };
+        let name = check
+            .mod_ref
+            .as_ref()
+            .and_then(|mod_ref| mod_ref.name_id.as_deref())
+            .unwrap_or(&check.url);
+        let details = match e {
+            ModCheckError::Renamed { new_url, .. } => format!("now <{new_url}>"),
+            ModCheckError::ModioError { .. }
+            | ModCheckError::TokenExpired { .. }
+            | ModCheckError::Unauthorized { .. }
+            | ModCheckError::GameNotFound { .. } => e.reason().unwrap_or_default(),
+            ModCheckError::ModfileRemoved { modfile_id, .. } => {
+                format!("modfile {modfile_id} no longer exists")
+            }
+            _ => String::new(),
+        };
+        writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            link(name, mod_url(&check.url, check.found.as_ref()).as_deref()),
+            e.kind(),
+            e.status_code().map_or("-".to_string(), |code| code.to_string()),
+            escape(&details),
+        )?;
+    }
+
+    if !report.duplicates.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "### Duplicates")?;
+        writeln!(out)?;
+        writeln!(out, "| Line | Mod | Duplicate of |")?;
+        writeln!(out, "| --- | --- | --- |")?;
+    }
+    for duplicate @ DuplicateMod { line, url, .. } in report.duplicates {
+        let mod_link = link(url, mod_url(url, None).as_deref());
+        writeln!(out, "| {line} | {mod_link} | {} |", escape(&duplicate.to_string()))?;
+    }
+
+    if !report.unrecognised.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "### Unrecognised input")?;
+        writeln!(out)?;
+        writeln!(out, "| Line | Text |")?;
+        writeln!(out, "| --- | --- |")?;
+    }
+    for UnrecognisedLine { line, text } in report.unrecognised {
+        writeln!(out, "| {line} | `{}` |", escape(text).replace('`', "'"))?;
+    }
+    Ok(())
+}
+
+fn link(text: &str, url: Option<&str>) -> String {
+    match url {
+        Some(url) => {
+            format!("[{}]({})", escape(text).replace(['[', ']'], ""), url.replace(' ', "%20"))
+        }
+        None => format!("`{}`", escape(text).replace('`', "")),
+    }
+}
+
+/// `https://` URL to link `url` to: `url` itself, the same mod.io URL with `https://` if it's an
+/// `http://`, `www.mod.io` or scheme-less one, or otherwise the profile URL of the `found` mod.
+fn mod_url(url: &str, found: Option<&Mod>) -> Option<String> {
+    if url.starts_with("https://") {
+        return Some(url.to_string());
+    }
+    match ModRef::parse(url) {
+        Some(ModRef { game: Some(_), .. }) => {
+            url.split_once("/g/").map(|(_, path)| format!("https://mod.io/g/{path}"))
+        }
+        _ => found.map(|r#mod| r#mod.profile_url.clone()),
+    }
+}
+
+/// Escape characters which would break out of a table cell.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
@@ -0,0 +1,52 @@
+use std::io::{self, Write};
+
+use crate::error::ModCheckError;
+use crate::mod_list::{DuplicateMod, UnrecognisedLine};
+use crate::report::Report;
+
+pub(super) fn write(report: &Report<'_>, out: &mut impl Write) -> io::Result<()> {
+    if let Some(expires) = report.token_expires {
+        writeln!(out, "INFO  {:<10} expires {}", "token", expires.format("%Y-%m-%d %H:%M UTC"))?;
+    }
+    for e in report.checks.iter().filter_map(|check| check.result.as_ref().err()) {
+        match e {
+            ModCheckError::InvalidModUrl { url } => writeln!(out, "ERROR {:<10} {url}", "invalid")?,
+            ModCheckError::ModNotFound { url } => writeln!(out, "ERROR {:<10} {url}", 404)?,
+            ModCheckError::ModioError { .. }
+            | ModCheckError::TokenExpired { .. }
+            | ModCheckError::Unauthorized { .. }
+            | ModCheckError::GameNotFound { .. } => {
+                let code = e.status_code().map_or("---".to_string(), |code| code.to_string());
+                match e.reason() {
+                    Some(reason) => writeln!(out, "ERROR {code:<10} {} ({reason})", e.url())?,
+                    None => writeln!(out, "ERROR {code:<10} {}", e.url())?,
+                }
+            }
+            ModCheckError::AmbiguousModUrl { url } => {
+                writeln!(out, "ERROR {:<10} {url}", "ambiguous")?
+            }
+            ModCheckError::ModRemoved { url, .. } => {
+                writeln!(out, "ERROR {:<10} {url}", "removed")?
+            }
+            ModCheckError::ModfileRemoved { url, .. } => {
+                writeln!(out, "ERROR {:<10} {url}", "modfile")?
+            }
+            ModCheckError::Renamed { old_url, new_url } => {
+                writeln!(out, "ERROR {:<10} {old_url} -> {new_url}", "renamed")?
+            }
+            ModCheckError::Hidden { url } => writeln!(out, "ERROR {:<10} {url}", "hidden")?,
+            ModCheckError::Deleted { url } => writeln!(out, "ERROR {:<10} {url}", "deleted")?,
+            ModCheckError::PendingModeration { url } => {
+                writeln!(out, "ERROR {:<10} {url}", "pending")?
+            }
+            ModCheckError::GameMismatch { url, .. } => writeln!(out, "ERROR {:<10} {url}", "game")?,
+        }
+    }
+    for duplicate @ DuplicateMod { line, url, .. } in report.duplicates {
+        writeln!(out, "WARN  {:<10} {url} ({duplicate})", format!("line {line}"))?;
+    }
+    for UnrecognisedLine { line, text } in report.unrecognised {
+        writeln!(out, "ERROR {:<10} {text}", format!("line {line}"))?;
+    }
+    Ok(())
+}
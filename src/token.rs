@@ -0,0 +1,103 @@
+use std::fmt;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// How long before the access token expires to start warning about it.
+pub const TOKEN_EXPIRY_WARNING: TimeDelta = TimeDelta::days(14);
+
+/// OAuth2 access token, with its expiry if known.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AccessToken {
+    pub token: String,
+    pub expires: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Error)]
+pub enum TokenError {
+    #[error("access token is empty")]
+    Empty,
+    #[error(
+        "invalid access token expiry `{value}`, expected a Unix timestamp or an RFC 3339 date"
+    )]
+    InvalidExpiry { value: String },
+    #[error("unexpected line {line} in the access token file, expected `expires=<date>`")]
+    UnexpectedLine { line: usize },
+}
+
+impl AccessToken {
+    /// `token` with the expiry read from its `exp` claim if it is a JWT, surrounding whitespace is
+    /// trimmed.
+    pub fn new(token: impl Into<String>) -> Self {
+        let token = token.into().trim().to_string();
+        let expires = jwt_expiry(&token);
+        AccessToken { token, expires }
+    }
+
+    /// Set the expiry to `value`, a Unix timestamp or an RFC 3339 date.
+    pub fn with_expiry(self, value: &str) -> Result<Self, TokenError> {
+        let value = value.trim();
+        let expires = match value.parse::<i64>() {
+            Ok(timestamp) => DateTime::from_timestamp(timestamp, 0),
+            Err(_) => DateTime::parse_from_rfc3339(value).ok().map(|date| date.to_utc()),
+        };
+        let expires =
+            expires.ok_or_else(|| TokenError::InvalidExpiry { value: value.to_string() })?;
+        Ok(self.expiring_at(expires))
+    }
+
+    /// Set the expiry to `expires`.
+    pub fn expiring_at(mut self, expires: DateTime<Utc>) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    /// Parse the contents of an access token file: the token on the first line, optionally followed
+    /// by an `expires=<date>` line with a Unix timestamp or an RFC 3339 date. Without it, the expiry
+    /// is read from the `exp` claim if the token is a JWT.
+    pub fn parse(text: &str) -> Result<AccessToken, TokenError> {
+        let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+        let mut token = lines
+            .find(|(_, line)| !line.is_empty())
+            .map(|(_, line)| AccessToken::new(line))
+            .ok_or(TokenError::Empty)?;
+
+        for (line, text) in lines.filter(|(_, text)| !text.is_empty()) {
+            let Some(value) = text.strip_prefix("expires=") else {
+                return Err(TokenError::UnexpectedLine { line });
+            };
+            token = token.with_expiry(value)?;
+        }
+        Ok(token)
+    }
+
+    /// Time left until the token expires, negative if it already has, `None` if unknown.
+    pub fn expires_in(&self, now: DateTime<Utc>) -> Option<TimeDelta> {
+        self.expires.map(|expires| expires - now)
+    }
+}
+
+impl fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessToken")
+            .field("token", &"<redacted>")
+            .field("expires", &self.expires)
+            .finish()
+    }
+}
+
+/// Expiry of `token` according to its `exp` claim, if it is a JWT.
+fn jwt_expiry(token: &str) -> Option<DateTime<Utc>> {
+    #[derive(Deserialize)]
+    struct Claims {
+        exp: i64,
+    }
+
+    let [_header, claims, _signature] = token.split('.').collect::<Vec<_>>().try_into().ok()?;
+    let claims = URL_SAFE_NO_PAD.decode(claims.trim_end_matches('=')).ok()?;
+    let Claims { exp } = serde_json::from_slice(&claims).ok()?;
+    DateTime::from_timestamp(exp, 0)
+}
@@ -0,0 +1,73 @@
+use std::sync::OnceLock;
+
+static RE_MOD: OnceLock<regex::Regex> = OnceLock::new();
+
+/// Regex matching mod URLs like `https://mod.io/g/<game>/m/<name_id>`, optionally followed by a
+/// `#<mod_id>/<modfile_id>` fragment. Also matches `http://`, `www.mod.io` or scheme-less URLs, with
+/// a trailing slash or a query string.
+pub fn re_mod() -> &'static regex::Regex {
+    RE_MOD.get_or_init(|| regex::Regex::new(r"^(?i:(?:https?://)?(?:www\.)?mod\.io)/g/(?P<game>[^/?#\s]+)/m/(?P<name_id>[^/?#\s]+)/?(?:\?[^#\s]*)?(?:#(?P<mod_id>\d+)(?:/(?P<modfile_id>\d+))?)?$").unwrap())
+}
+
+/// Canonical reference to a mod, as parsed from a line of a mod list.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModRef {
+    /// `name_id` of the game the mod belongs to, e.g. `drg`. `None` for bare ids and `name_id`s,
+    /// which refer to mods of the checked game.
+    pub game: Option<String>,
+    /// `None` if the mod is only referenced by its id.
+    pub name_id: Option<String>,
+    pub mod_id: Option<u32>,
+    pub modfile_id: Option<u32>,
+}
+
+impl ModRef {
+    /// Parse a mod URL matched by [`re_mod`], a bare mod id or a bare `name_id`, ignoring
+    /// surrounding whitespace.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if let Ok(mod_id) = input.parse() {
+            return Some(ModRef::bare(None, Some(mod_id)));
+        }
+        if is_name_id(input) {
+            return Some(ModRef::bare(Some(input.to_string()), None));
+        }
+
+        let caps = re_mod().captures(input)?;
+        Some(ModRef {
+            game: Some(caps.name("game")?.as_str().to_string()),
+            name_id: Some(caps.name("name_id")?.as_str().to_string()),
+            mod_id: caps.name("mod_id").and_then(|id| id.as_str().parse().ok()),
+            modfile_id: caps.name("modfile_id").and_then(|id| id.as_str().parse().ok()),
+        })
+    }
+
+    /// Whether `self` and `other` refer to the same mod, going by their mod ids if both have one and
+    /// by their `name_id`s otherwise. References without a game are assumed to be of the game of the
+    /// other reference. Pinned modfiles aren't compared, and a reference only by id
+    /// never matches one only by `name_id`, as telling requires looking the mod up.
+    pub fn same_mod(&self, other: &ModRef) -> bool {
+        if let (Some(game), Some(other_game)) = (&self.game, &other.game) {
+            if game != other_game {
+                return false;
+            }
+        }
+        match (self.mod_id, other.mod_id, &self.name_id, &other.name_id) {
+            (Some(id), Some(other_id), ..) => id == other_id,
+            (.., Some(name_id), Some(other_name_id)) => name_id == other_name_id,
+            _ => false,
+        }
+    }
+
+    fn bare(name_id: Option<String>, mod_id: Option<u32>) -> Self {
+        ModRef { game: None, name_id, mod_id, modfile_id: None }
+    }
+}
+
+/// Whether `input` looks like a mod's `name_id`, e.g. `sandbox-utilities`.
+fn is_name_id(input: &str) -> bool {
+    !input.is_empty()
+        && input
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_')
+}
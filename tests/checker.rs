@@ -0,0 +1,124 @@
+//! Classification of mods by [`ModChecker`], against the in-memory [`FixtureBackend`].
+
+use modio_modcheck::backend::FixtureBackend;
+use modio_modcheck::{
+    AuthError, Game, Mod, ModChecker, Modfile, OutcomeKind, User, MODIO_DRG_ID,
+    MOD_STATUS_ACCEPTED, MOD_STATUS_DELETED, MOD_STATUS_NOT_ACCEPTED,
+};
+
+fn fixture_mod(id: u32, name_id: &str, status: u32, visible: u32) -> Mod {
+    Mod {
+        id,
+        name_id: name_id.to_string(),
+        status,
+        visible,
+        profile_url: format!("https://mod.io/g/drg/m/{name_id}"),
+    }
+}
+
+fn user() -> User {
+    User { id: 1, name_id: "test-user".to_string(), username: "Test User".to_string() }
+}
+
+async fn outcomes(backend: FixtureBackend, urls: &[&str]) -> Vec<OutcomeKind> {
+    let checker = ModChecker::new(backend, MODIO_DRG_ID);
+    let checks = checker.check_all(urls.iter().copied(), |_| {}).await;
+    checks.iter().map(|check| check.kind()).collect()
+}
+
+#[tokio::test]
+async fn mods_are_classified() {
+    let backend = FixtureBackend::new()
+        .with_mod(MODIO_DRG_ID, fixture_mod(1, "live", MOD_STATUS_ACCEPTED, 1))
+        .with_mod(MODIO_DRG_ID, fixture_mod(2, "hidden", MOD_STATUS_ACCEPTED, 0))
+        .with_mod(MODIO_DRG_ID, fixture_mod(3, "deleted", MOD_STATUS_DELETED, 1))
+        .with_mod(MODIO_DRG_ID, fixture_mod(4, "pending", MOD_STATUS_NOT_ACCEPTED, 1))
+        .with_mod(MODIO_DRG_ID, fixture_mod(5, "twice", MOD_STATUS_ACCEPTED, 1))
+        .with_mod(MODIO_DRG_ID, fixture_mod(6, "twice", MOD_STATUS_ACCEPTED, 1))
+        .with_mod(MODIO_DRG_ID, fixture_mod(7, "new-name", MOD_STATUS_ACCEPTED, 1))
+        .with_modfile(Modfile { id: 10, mod_id: 1 });
+
+    let outcomes = outcomes(
+        backend,
+        &[
+            "https://mod.io/g/drg/m/live",
+            "https://mod.io/g/drg/m/live#1/10",
+            "https://mod.io/g/drg/m/live#1/11",
+            "https://mod.io/g/drg/m/missing",
+            "https://mod.io/g/drg/m/live#8",
+            "https://mod.io/g/drg/m/hidden",
+            "https://mod.io/g/drg/m/deleted",
+            "https://mod.io/g/drg/m/pending",
+            "https://mod.io/g/drg/m/twice",
+            "https://mod.io/g/drg/m/old-name#7",
+            "not a mod",
+        ],
+    )
+    .await;
+
+    assert_eq!(
+        outcomes,
+        [
+            OutcomeKind::Ok,
+            OutcomeKind::Ok,
+            OutcomeKind::ModfileRemoved,
+            OutcomeKind::NotFound,
+            OutcomeKind::ModRemoved,
+            OutcomeKind::Hidden,
+            OutcomeKind::Deleted,
+            OutcomeKind::PendingModeration,
+            OutcomeKind::Ambiguous,
+            OutcomeKind::Renamed,
+            OutcomeKind::InvalidUrl,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn lookup_errors_are_reported() {
+    let backend = FixtureBackend::new()
+        .with_mod(MODIO_DRG_ID, fixture_mod(1, "live", MOD_STATUS_ACCEPTED, 1))
+        .with_modfile(Modfile { id: 10, mod_id: 1 });
+
+    let by_name = outcomes(
+        FixtureBackend::new().with_error("broken", 500),
+        &["https://mod.io/g/drg/m/broken"],
+    );
+    let by_id =
+        outcomes(FixtureBackend::new().with_error(2, 503), &["https://mod.io/g/drg/m/broken#2"]);
+    let modfile =
+        outcomes(backend.with_modfile_error(10, 500), &["https://mod.io/g/drg/m/live#1/10"]);
+
+    assert_eq!(by_name.await, [OutcomeKind::ModioError]);
+    assert_eq!(by_id.await, [OutcomeKind::ModioError]);
+    assert_eq!(modfile.await, [OutcomeKind::ModioError]);
+}
+
+#[tokio::test]
+async fn token_check_errors_are_reported() {
+    let rejected = ModChecker::new(FixtureBackend::new(), MODIO_DRG_ID);
+    let failed =
+        ModChecker::new(FixtureBackend::new().with_user(user()).with_me_error(500), MODIO_DRG_ID);
+    let other_user = ModChecker::new(FixtureBackend::new().with_user(user()), MODIO_DRG_ID);
+
+    assert!(matches!(rejected.verify_token(None).await, Err(AuthError::Rejected { .. })));
+    assert!(matches!(failed.verify_token(None).await, Err(AuthError::ModioError { .. })));
+    assert!(matches!(
+        other_user.verify_token(Some(2)).await,
+        Err(AuthError::WrongUser { user_id: 2, .. })
+    ));
+    assert_eq!(other_user.verify_token(None).await.unwrap(), user());
+}
+
+#[tokio::test]
+async fn mods_of_other_games_are_reported() {
+    let other_game = Game { id: 5, name_id: "other-game".to_string(), name: "Other".to_string() };
+    let by_name_id = ModChecker::builder().token("token").game_id(5).game_name_id("other-game");
+    let by_game = ModChecker::builder().token("token").game(other_game);
+
+    for builder in [by_name_id, by_game] {
+        let checker = builder.build().unwrap();
+        let check = checker.check("https://mod.io/g/drg/m/sandbox-utilities").await;
+        assert_eq!(check.kind(), OutcomeKind::GameMismatch);
+    }
+}
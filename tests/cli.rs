@@ -0,0 +1,1253 @@
+mod common;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{TimeDelta, Utc};
+use common::*;
+use serde_json::json;
+
+use std::io::Write;
+use std::process::Stdio;
+use std::time::Duration;
+
+const SANDBOX: &str = "https://mod.io/g/drg/m/sandbox-utilities";
+const MISSING: &str = "https://mod.io/g/drg/m/missing";
+
+#[test]
+fn existing_mod_is_not_reported() {
+    let dir = workdir("existing_mod_is_not_reported");
+    let server = MockModio::start([(
+        "sandbox-utilities",
+        Response::mods(&[Mod::new(1, "sandbox-utilities")]),
+    )]);
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &[]);
+
+    assert!(output.status.success());
+    assert_eq!(stderr_lines(&output), ["check completed, writing log to `errors.log`"]);
+    assert_eq!(errors_log(&dir), "");
+    assert_eq!(
+        server.requests(),
+        ["/v1/games/2475/mods?name_id-in=sandbox-utilities&visible-in=0,1&status-in=0,1,3&_offset=0&_limit=100"]
+    );
+}
+
+#[test]
+fn missing_mod_is_reported_as_404() {
+    let dir = workdir("missing_mod_is_reported_as_404");
+    let server = MockModio::start([("missing", Response::mods(&[]))]);
+
+    let output = run(&dir, &server, &format!("{MISSING}\n{SANDBOX}\n"), &[]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            format!("       ERROR 404 {MISSING}"),
+            format!("       ERROR 404 {SANDBOX}"),
+            "check completed, writing log to `errors.log`".to_string(),
+        ]
+    );
+    assert_eq!(
+        errors_log(&dir),
+        format!("ERROR 404        {MISSING}\nERROR 404        {SANDBOX}\n")
+    );
+}
+
+#[test]
+fn multiple_matches_are_ambiguous() {
+    let dir = workdir("multiple_matches_are_ambiguous");
+    let server = MockModio::start([(
+        "sandbox-utilities",
+        Response::mods(&[Mod::new(1, "sandbox-utilities"), Mod::new(2, "sandbox-utilities")]),
+    )]);
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &[]);
+
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            format!("       ERROR   - {SANDBOX}"),
+            "check completed, writing log to `errors.log`".into()
+        ]
+    );
+    assert_eq!(errors_log(&dir), format!("ERROR ambiguous  {SANDBOX}\n"));
+}
+
+#[test]
+fn error_statuses_are_reported() {
+    let field_errors = json!({
+        "error": {
+            "code": 422,
+            "error_ref": 13009,
+            "message": "Validation Failed.",
+            "errors": {"name_id": "The name id must be a string."},
+        }
+    });
+    for (name, response, label, reason, exit_code) in [
+        (
+            "expired",
+            Response::error(401, 11005, "Something went wrong."),
+            "401",
+            "access token expired or revoked",
+            3,
+        ),
+        (
+            "unauthorized",
+            Response::error(401, 11000, "Authentication required."),
+            "401",
+            "Authentication required.",
+            3,
+        ),
+        ("forbidden", Response::error(403, 15023, "Forbidden."), "403", "Forbidden.", 4),
+        ("game", Response::error(404, 14001, "Game not found."), "404", "game not found", 2),
+        (
+            "invalid",
+            Response::json(422, field_errors.to_string()),
+            "422",
+            "Validation Failed.; name_id: The name id must be a string.",
+            4,
+        ),
+        ("internal", Response::error(500, 10000, "Internal error."), "500", "Internal error.", 4),
+    ] {
+        let dir = workdir(&format!("error_statuses_are_reported_{name}"));
+        let server = MockModio::start([("sandbox-utilities", response)]);
+
+        let output = run(&dir, &server, &format!("{SANDBOX}\n"), &["--max-attempts", "1"]);
+
+        assert_eq!(output.status.code(), Some(exit_code), "{name}");
+        assert_eq!(
+            stderr_lines(&output),
+            [
+                format!("       ERROR {label} {SANDBOX} ({reason})"),
+                "check completed, writing log to `errors.log`".to_string(),
+            ]
+        );
+        assert_eq!(errors_log(&dir), format!("ERROR {label}        {SANDBOX} ({reason})\n"));
+    }
+}
+
+#[test]
+fn malformed_json_is_reported_without_status() {
+    let dir = workdir("malformed_json_is_reported_without_status");
+    let server = MockModio::start([("sandbox-utilities", Response::json(200, "{\"data\": ["))]);
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &[]);
+
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            format!("       ERROR   - {SANDBOX}"),
+            "check completed, writing log to `errors.log`".into()
+        ]
+    );
+    assert_eq!(errors_log(&dir), format!("ERROR ---        {SANDBOX}\n"));
+}
+
+#[test]
+fn unrecognised_lines_are_reported() {
+    let dir = workdir("unrecognised_lines_are_reported");
+    let server = MockModio::start([(
+        "sandbox-utilities",
+        Response::mods(&[Mod::new(1, "sandbox-utilities")]),
+    )]);
+    let mod_list = format!("not a mod\n\n# {MISSING}\n{SANDBOX}\nhttps://example.com/mods/a\n");
+
+    let output = run(&dir, &server, &mod_list, &["--format", "json"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            "UNRECOGNISED line 1: not a mod",
+            "UNRECOGNISED line 5: https://example.com/mods/a",
+            "check completed, writing log to `errors.log`",
+        ]
+    );
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("errors.log")).unwrap()).unwrap();
+    assert_eq!(
+        report["unrecognised"],
+        serde_json::json!([
+            { "line": 1, "text": "not a mod" },
+            { "line": 5, "text": "https://example.com/mods/a" },
+        ])
+    );
+    assert_eq!(server.requests().len(), 1);
+
+    let output = run(&dir, &server, &mod_list, &["--strict"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        errors_log(&dir),
+        "ERROR line 1     not a mod\nERROR line 5     https://example.com/mods/a\n"
+    );
+}
+
+#[test]
+fn mods_are_looked_up_in_batches() {
+    let dir = workdir("mods_are_looked_up_in_batches");
+    let server = MockModio::start([]);
+    let urls = (0..120).map(|i| format!("https://mod.io/g/drg/m/missing-{i}")).collect::<Vec<_>>();
+
+    let output = run(&dir, &server, &urls.join("\n"), &["--concurrency", "3"]);
+
+    let mut expected = urls.iter().map(|url| format!("       ERROR 404 {url}")).collect::<Vec<_>>();
+    expected.push("check completed, writing log to `errors.log`".to_string());
+    assert_eq!(stderr_lines(&output), expected);
+
+    let mut requests = server.requests();
+    requests.sort();
+    let batch = |range: std::ops::Range<usize>| {
+        let name_ids = range.map(|i| format!("missing-{i}")).collect::<Vec<_>>().join(",");
+        format!("/v1/games/2475/mods?name_id-in={name_ids}&visible-in=0,1&status-in=0,1,3&_offset=0&_limit=100")
+    };
+    let mut expected = vec![batch(0..50), batch(50..100), batch(100..120)];
+    expected.sort();
+    assert_eq!(requests, expected);
+}
+
+#[test]
+fn paginated_results_are_fetched_completely() {
+    let dir = workdir("paginated_results_are_fetched_completely");
+    let names = ["a", "b", "c", "d", "e"];
+    let server = MockModio::with_page_size(
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (*name, Response::mods(&[Mod::new(i as u32, name)]))),
+        2,
+    );
+    let urls = names.map(|name| format!("https://mod.io/g/drg/m/{name}"));
+
+    let output = run(&dir, &server, &urls.join("\n"), &[]);
+
+    assert_eq!(stderr_lines(&output), ["check completed, writing log to `errors.log`"]);
+    assert_eq!(
+        server.requests(),
+        [0, 2, 4].map(|offset| format!(
+            "/v1/games/2475/mods?name_id-in=a,b,c,d,e&visible-in=0,1&status-in=0,1,3&_offset={offset}&_limit=100"
+        ))
+    );
+}
+
+#[test]
+fn rate_limited_request_is_retried_after_waiting() {
+    let dir = workdir("rate_limited_request_is_retried_after_waiting");
+    let server = MockModio::start([
+        (
+            "sandbox-utilities",
+            Response::error(429, 11008, "You have made too many requests.")
+                .header("Retry-After", 1),
+        ),
+        ("sandbox-utilities", Response::mods(&[Mod::new(1, "sandbox-utilities")])),
+    ]);
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &[]);
+
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            "        INFO waiting 1 second to not trigger mod.io rate limit",
+            "check completed, writing log to `errors.log`",
+        ]
+    );
+    assert_eq!(errors_log(&dir), "");
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn exhausted_rate_limit_pauses_following_requests() {
+    let dir = workdir("exhausted_rate_limit_pauses_following_requests");
+    let server = MockModio::with_page_size(
+        [
+            (
+                "sandbox-utilities",
+                Response::mods(&[Mod::new(1, "sandbox-utilities")])
+                    .header("X-RateLimit-Remaining", 0)
+                    .header("X-RateLimit-RetryAfter", 1),
+            ),
+            ("other", Response::mods(&[Mod::new(2, "other")])),
+        ],
+        1,
+    );
+
+    let start = std::time::Instant::now();
+    let output = run(&dir, &server, &format!("{SANDBOX}\nhttps://mod.io/g/drg/m/other\n"), &[]);
+
+    assert!(start.elapsed() >= std::time::Duration::from_millis(900));
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            "        INFO waiting 1 second to not trigger mod.io rate limit",
+            "check completed, writing log to `errors.log`",
+        ]
+    );
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn concurrency_limits_requests_in_flight() {
+    let mods = [Mod::new(1, "a").modfiles(&[11]), Mod::new(2, "b").modfiles(&[12])];
+    let mod_list = "https://mod.io/g/drg/m/a#1/11\nhttps://mod.io/g/drg/m/b#2/12\n";
+    for concurrency in [1, 2] {
+        let dir = workdir(&format!("concurrency_limits_requests_in_flight_{concurrency}"));
+        let server = MockModio::start([("a", Response::mods(&mods))]);
+        server.delay_responses(Duration::from_millis(100));
+
+        let output = run(&dir, &server, mod_list, &["--concurrency", &concurrency.to_string()]);
+
+        assert_eq!(output.status.code(), Some(0));
+        // One request by name_id, one by id and one per modfile.
+        assert_eq!(server.requests().len(), 4);
+        assert_eq!(server.peak_in_flight(), concurrency);
+    }
+}
+
+#[test]
+fn concurrently_exhausted_rate_limit_is_announced_once() {
+    let dir = workdir("concurrently_exhausted_rate_limit_is_announced_once");
+    let exhausted = |mods: &[Mod]| {
+        Response::mods(mods).header("X-RateLimit-Remaining", 0).header("X-RateLimit-RetryAfter", 1)
+    };
+    // Two batches, looked up at the same time, each with a second page to fetch after the pause.
+    let server = MockModio::with_page_size(
+        [
+            ("a", exhausted(&[Mod::new(1, "a"), Mod::new(2, "a-2")])),
+            ("b", exhausted(&[Mod::new(3, "b"), Mod::new(4, "b-2")])),
+        ],
+        1,
+    );
+    server.delay_responses(Duration::from_millis(200));
+    let fillers = (1..50).map(|i| format!("filler-{i}\n")).collect::<String>();
+
+    let output = run(&dir, &server, &format!("a\n{fillers}b\n"), &[]);
+
+    let info = stderr_lines(&output).into_iter().filter(|line| line.contains("INFO"));
+    assert_eq!(
+        info.collect::<Vec<_>>(),
+        ["        INFO waiting 1 second to not trigger mod.io rate limit"]
+    );
+    assert_eq!(server.requests().len(), 4);
+}
+
+#[test]
+fn transient_failures_are_retried() {
+    let dir = workdir("transient_failures_are_retried");
+    let server = MockModio::start([
+        ("sandbox-utilities", Response::error(503, 10000, "Service unavailable.")),
+        ("sandbox-utilities", Response::error(502, 10000, "Bad gateway.")),
+        ("sandbox-utilities", Response::mods(&[Mod::new(1, "sandbox-utilities")])),
+    ]);
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &[]);
+
+    assert_eq!(stderr_lines(&output), ["check completed, writing log to `errors.log`"]);
+    assert_eq!(errors_log(&dir), "");
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn persistent_failures_are_reported_after_max_attempts() {
+    let dir = workdir("persistent_failures_are_reported_after_max_attempts");
+    let server =
+        MockModio::start([("sandbox-utilities", Response::error(500, 10000, "Internal error."))]);
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &["--max-attempts", "2"]);
+
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            format!("       ERROR 500 {SANDBOX} (Internal error.)"),
+            "check completed, writing log to `errors.log`".into()
+        ]
+    );
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn client_errors_are_not_retried() {
+    let dir = workdir("client_errors_are_not_retried");
+    let server = MockModio::start([(
+        "sandbox-utilities",
+        Response::error(401, 11000, "This request requires authentication."),
+    )]);
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &[]);
+
+    assert_eq!(
+        errors_log(&dir),
+        format!("ERROR 401        {SANDBOX} (This request requires authentication.)\n")
+    );
+    assert!(!output.stderr.is_empty());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn pinned_mod_and_modfile_are_verified() {
+    let dir = workdir("pinned_mod_and_modfile_are_verified");
+    let server = MockModio::start([
+        ("sandbox-utilities", Response::mods(&[Mod::new(1, "sandbox-utilities").modfiles(&[10])])),
+        ("reused-name", Response::mods(&[Mod::new(3, "reused-name")])),
+        ("other", Response::mods(&[Mod::new(4, "other").modfiles(&[12])])),
+    ]);
+    let pinned = format!("{SANDBOX}#1/10");
+    let modfile_removed = "https://mod.io/g/drg/m/other#4/11";
+    let mod_removed = "https://mod.io/g/drg/m/reused-name#2";
+
+    let output = run(&dir, &server, &format!("{pinned}\n{modfile_removed}\n{mod_removed}\n"), &[]);
+
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            format!("       ERROR 404 {modfile_removed}"),
+            format!("       ERROR 404 {mod_removed}"),
+            "check completed, writing log to `errors.log`".to_string(),
+        ]
+    );
+    assert_eq!(
+        errors_log(&dir),
+        format!("ERROR modfile    {modfile_removed}\nERROR removed    {mod_removed}\n")
+    );
+    let mut requests = server.requests();
+    requests.sort();
+    assert_eq!(
+        requests,
+        [
+            "/v1/games/2475/mods/1/files/10",
+            "/v1/games/2475/mods/4/files/11",
+            "/v1/games/2475/mods?id-in=1,4,2&visible-in=0,1&status-in=0,1,3&_offset=0&_limit=100",
+            "/v1/games/2475/mods?name_id-in=sandbox-utilities,other,reused-name&visible-in=0,1&status-in=0,1,3&_offset=0&_limit=100",
+        ]
+    );
+}
+
+#[test]
+fn renamed_mod_is_reported_with_new_url() {
+    let dir = workdir("renamed_mod_is_reported_with_new_url");
+    let server = MockModio::start([
+        ("new-name", Response::mods(&[Mod::new(5, "new-name").modfiles(&[50])])),
+        ("reused-name", Response::mods(&[Mod::new(7, "reused-name")])),
+        ("moved-away", Response::mods(&[Mod::new(6, "moved-away")])),
+    ]);
+    let renamed = "https://mod.io/g/drg/m/old-name#5/50";
+    let name_reused = "https://mod.io/g/drg/m/reused-name#6";
+
+    let output = run(&dir, &server, &format!("{renamed}\n{name_reused}\n"), &[]);
+
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            format!("     RENAMED   - {renamed} -> https://mod.io/g/drg/m/new-name"),
+            format!("     RENAMED   - {name_reused} -> https://mod.io/g/drg/m/moved-away"),
+            "check completed, writing log to `errors.log`".to_string(),
+        ]
+    );
+    assert_eq!(
+        errors_log(&dir),
+        format!(
+            "ERROR renamed    {renamed} -> https://mod.io/g/drg/m/new-name\n\
+             ERROR renamed    {name_reused} -> https://mod.io/g/drg/m/moved-away\n"
+        )
+    );
+}
+
+#[test]
+fn hidden_deleted_and_pending_mods_are_distinguished() {
+    let dir = workdir("hidden_deleted_and_pending_mods_are_distinguished");
+    let server = MockModio::start([
+        ("hidden", Response::mods(&[Mod::new(1, "hidden").hidden()])),
+        ("deleted", Response::mods(&[Mod::new(2, "deleted").status(3)])),
+        ("pending", Response::mods(&[Mod::new(3, "pending").status(0)])),
+    ]);
+    let [hidden, deleted, pending] =
+        ["hidden", "deleted", "pending"].map(|name| format!("https://mod.io/g/drg/m/{name}"));
+
+    let output = run(&dir, &server, &format!("{hidden}\n{deleted}#2\n{pending}\n"), &[]);
+
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            format!("      HIDDEN   - {hidden}"),
+            format!("     DELETED   - {deleted}#2"),
+            format!("     PENDING   - {pending}"),
+            "check completed, writing log to `errors.log`".to_string(),
+        ]
+    );
+    assert_eq!(
+        errors_log(&dir),
+        format!(
+            "ERROR hidden     {hidden}\nERROR deleted    {deleted}#2\nERROR pending    {pending}\n"
+        )
+    );
+}
+
+#[test]
+fn fix_writes_corrected_mod_list() {
+    let dir = workdir("fix_writes_corrected_mod_list");
+    let server = MockModio::start([
+        ("sandbox-utilities", Response::mods(&[Mod::new(1, "sandbox-utilities")])),
+        ("new-name", Response::mods(&[Mod::new(5, "new-name")])),
+        ("hidden", Response::mods(&[Mod::new(6, "hidden").hidden()])),
+    ]);
+    let mod_list = format!(
+        "{SANDBOX}\n\
+         https://mod.io/g/drg/m/old-name#5\n\
+         {MISSING}\n\
+         https://mod.io/g/drg/m/hidden\n\
+         {SANDBOX}\n\
+         https://mod.io/g/drg/m/new-name\n\
+         {SANDBOX}#1\n\
+         https://mod.io/g/drg/m/old-name\n"
+    );
+
+    let output = run_subcommand(&dir, &server, &["fix"], &mod_list, &[]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr_lines(&output).last().unwrap(), "writing fixed mod list to `mods.fixed.txt`");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("mods.fixed.txt")).unwrap(),
+        format!(
+            "{SANDBOX}\n\
+             https://mod.io/g/drg/m/new-name\n\
+             # {MISSING}\n\
+             # https://mod.io/g/drg/m/hidden\n"
+        )
+    );
+
+    run_subcommand(
+        &dir,
+        &server,
+        &["fix"],
+        &mod_list,
+        &["--unavailable", "drop", "--fixed", "out.txt"],
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("out.txt")).unwrap(),
+        format!("{SANDBOX}\nhttps://mod.io/g/drg/m/new-name\n")
+    );
+}
+
+#[test]
+fn json_report_has_one_record_per_url() {
+    let dir = workdir("json_report_has_one_record_per_url");
+    let server = MockModio::start([
+        ("sandbox-utilities", Response::mods(&[Mod::new(1, "sandbox-utilities")])),
+        ("new-name", Response::mods(&[Mod::new(5, "new-name")])),
+    ]);
+    let renamed = "https://mod.io/g/drg/m/old-name#5";
+
+    let output = run(
+        &dir,
+        &server,
+        &format!("{SANDBOX}\n{renamed}\n{MISSING}\n"),
+        &["--format", "json", "--report", "report.json"],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr_lines(&output).last().unwrap(),
+        "check completed, writing log to `report.json`"
+    );
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("report.json")).unwrap()).unwrap();
+    let mod_json = |id: u32, name_id: &str| {
+        serde_json::json!({
+            "id": id,
+            "name_id": name_id,
+            "status": 1,
+            "visible": 1,
+            "profile_url": format!("https://mod.io/g/drg/m/{name_id}"),
+        })
+    };
+    assert_eq!(
+        report,
+        serde_json::json!({
+            "checks": [
+                {
+                    "url": SANDBOX,
+                    "name_id": "sandbox-utilities",
+                    "mod_id": null,
+                    "modfile_id": null,
+                    "outcome": "ok",
+                    "status": null,
+                    "message": null,
+                    "new_url": null,
+                    "mod": mod_json(1, "sandbox-utilities"),
+                },
+                {
+                    "url": renamed,
+                    "name_id": "old-name",
+                    "mod_id": 5,
+                    "modfile_id": null,
+                    "outcome": "renamed",
+                    "status": null,
+                    "message": format!("mod renamed: <{renamed}> is now <https://mod.io/g/drg/m/new-name>"),
+                    "new_url": "https://mod.io/g/drg/m/new-name",
+                    "mod": mod_json(5, "new-name"),
+                },
+                {
+                    "url": MISSING,
+                    "name_id": "missing",
+                    "mod_id": null,
+                    "modfile_id": null,
+                    "outcome": "not_found",
+                    "status": 404,
+                    "message": format!("mod not found: <{MISSING}>"),
+                    "new_url": null,
+                    "mod": null,
+                },
+            ],
+            "duplicates": [],
+            "unrecognised": [],
+            "token_expires": null,
+        })
+    );
+    assert!(!dir.join("errors.log").exists());
+}
+
+#[test]
+fn csv_and_markdown_reports_list_problem_mods() {
+    let dir = workdir("csv_and_markdown_reports_list_problem_mods");
+    let server = MockModio::start([
+        ("sandbox-utilities", Response::mods(&[Mod::new(1, "sandbox-utilities")])),
+        ("new-name", Response::mods(&[Mod::new(5, "new-name")])),
+        ("hidden", Response::mods(&[Mod::new(2, "hidden").hidden()])),
+    ]);
+    let renamed = "https://mod.io/g/drg/m/old-name#5";
+    let mod_list = format!("{SANDBOX}\n{renamed}\n{MISSING}\n");
+    let markdown_list = format!(
+        "{mod_list}http://mod.io/g/drg/m/gone\nwww.mod.io/g/drg/m/lost\nhidden\nmissing-too\n\
+         mod.io/g/drg/m/sandbox-utilities\n"
+    );
+
+    let output = run(&dir, &server, &mod_list, &["--format", "csv", "--report", "report.csv"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("report.csv")).unwrap(),
+        format!(
+            "url,name_id,mod_id,modfile_id,outcome,status,message,new_url,profile_url\r\n\
+             {SANDBOX},sandbox-utilities,,,ok,,,,{SANDBOX}\r\n\
+             {renamed},old-name,5,,renamed,,mod renamed: <{renamed}> is now \
+             <https://mod.io/g/drg/m/new-name>,https://mod.io/g/drg/m/new-name,\
+             https://mod.io/g/drg/m/new-name\r\n\
+             {MISSING},missing,,,not_found,404,mod not found: <{MISSING}>,,\r\n"
+        )
+    );
+
+    let output =
+        run(&dir, &server, &markdown_list, &["--format", "markdown", "--report", "report.md"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("report.md")).unwrap(),
+        format!(
+            "## mod.io modcheck report\n\
+             \n\
+             6 of 7 mods have problems.\n\
+             \n\
+             | Mod | Problem | Status | Details |\n\
+             | --- | --- | --- | --- |\n\
+             | [old-name]({renamed}) | renamed | - | now <https://mod.io/g/drg/m/new-name> |\n\
+             | [missing]({MISSING}) | not_found | 404 |  |\n\
+             | [gone](https://mod.io/g/drg/m/gone) | not_found | 404 |  |\n\
+             | [lost](https://mod.io/g/drg/m/lost) | not_found | 404 |  |\n\
+             | [hidden](https://mod.io/g/drg/m/hidden) | hidden | - |  |\n\
+             | `missing-too` | not_found | 404 |  |\n\
+             \n\
+             ### Duplicates\n\
+             \n\
+             | Line | Mod | Duplicate of |\n\
+             | --- | --- | --- |\n\
+             | 8 | [mod.io/g/drg/m/sandbox-utilities]({SANDBOX}) | same mod as line 1 |\n"
+        )
+    );
+}
+
+#[test]
+fn junit_report_has_one_test_case_per_url() {
+    let dir = workdir("junit_report_has_one_test_case_per_url");
+    let server = MockModio::start([
+        ("sandbox-utilities", Response::mods(&[Mod::new(1, "sandbox-utilities")])),
+        ("hidden", Response::mods(&[Mod::new(2, "hidden").hidden()])),
+    ]);
+    let hidden = "https://mod.io/g/drg/m/hidden";
+
+    let output = run(
+        &dir,
+        &server,
+        &format!("{SANDBOX}\n{hidden}\n{MISSING}\n{SANDBOX}\nnot a mod\n"),
+        &["--format", "junit", "--report", "report.xml"],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("report.xml")).unwrap(),
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="modio-modcheck" tests="5" failures="2" skipped="2">
+  <testsuite name="mod list" tests="3" failures="2" errors="0">
+    <testcase classname="modio-modcheck" name="{SANDBOX}"/>
+    <testcase classname="modio-modcheck" name="{hidden}">
+      <failure type="hidden" message="mod hidden: &lt;{hidden}&gt;">status: -&#10;mod hidden: &lt;{hidden}&gt;</failure>
+    </testcase>
+    <testcase classname="modio-modcheck" name="{MISSING}">
+      <failure type="not_found" message="mod not found: &lt;{MISSING}&gt;">status: 404&#10;mod not found: &lt;{MISSING}&gt;</failure>
+    </testcase>
+  </testsuite>
+  <testsuite name="duplicates" tests="1" failures="0" errors="0" skipped="1">
+    <testcase classname="modio-modcheck" name="line 4: {SANDBOX}">
+      <skipped message="same mod as line 1"/>
+    </testcase>
+  </testsuite>
+  <testsuite name="unrecognised input" tests="1" failures="0" errors="0" skipped="1">
+    <testcase classname="modio-modcheck" name="line 5: not a mod">
+      <skipped message="not a mod URL"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        )
+    );
+}
+
+#[test]
+fn fail_on_selects_failing_outcomes() {
+    let dir = workdir("fail_on_selects_failing_outcomes");
+    let server = MockModio::start([
+        ("new-name", Response::mods(&[Mod::new(5, "new-name")])),
+        ("hidden", Response::mods(&[Mod::new(2, "hidden").hidden()])),
+    ]);
+    let mod_list = "https://mod.io/g/drg/m/old-name#5\nhttps://mod.io/g/drg/m/hidden\n";
+
+    let output = run(&dir, &server, mod_list, &["--fail-on", "not_found,deleted"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run(&dir, &server, mod_list, &["--fail-on", "renamed"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run(&dir, &server, mod_list, &["--fail-on", "gone"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = run(&dir, &server, mod_list, &["--fail-on", "modio_error"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn fail_on_does_not_hide_modio_errors() {
+    for (name, response, exit_code) in [
+        ("unauthorized", Response::error(401, 11000, "Authentication required."), 3),
+        ("internal", Response::error(500, 10000, "Internal error."), 4),
+    ] {
+        let dir = workdir(&format!("fail_on_does_not_hide_modio_errors_{name}"));
+        let server = MockModio::start([("sandbox-utilities", response)]);
+
+        let args = ["--fail-on", "not_found", "--max-attempts", "1"];
+        let output = run(&dir, &server, &format!("{SANDBOX}\n"), &args);
+
+        assert_eq!(output.status.code(), Some(exit_code), "{name}");
+    }
+}
+
+#[test]
+fn zero_max_attempts_is_rejected() {
+    let dir = workdir("zero_max_attempts_is_rejected");
+    let server = MockModio::start([]);
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &["--max-attempts", "0"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn missing_token_file_is_an_input_error() {
+    let dir = workdir("missing_token_file_is_an_input_error");
+    let server = MockModio::start([]);
+    std::fs::remove_file(dir.join("token.txt")).unwrap();
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &[]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stderr_lines(&output), ["Error: `token.txt` does not exist"]);
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn invalid_tokens_abort_before_checking() {
+    let other_user = r#"{"id":2,"name_id":"someone-else","username":"Someone Else"}"#;
+    for (name, response, error, exit_code) in [
+        (
+            "expired",
+            Response::error(401, 11005, "Access token expired."),
+            "access token expired or revoked",
+            3,
+        ),
+        (
+            "other_user",
+            Response::json(200, other_user),
+            "access token belongs to user 2 (`someone-else`), not to user 1",
+            3,
+        ),
+        (
+            "unavailable",
+            Response::error(503, 10000, "Service unavailable."),
+            "mod.io error validating access token: mod.io responded with status 503 \
+             (error_ref 10000): Service unavailable.",
+            4,
+        ),
+    ] {
+        let dir = workdir(&format!("invalid_tokens_abort_before_checking_{name}"));
+        let server = MockModio::start([(ME, response)]);
+
+        let output = run(&dir, &server, &format!("{SANDBOX}\n"), &["--max-attempts", "1"]);
+
+        assert_eq!(output.status.code(), Some(exit_code), "{name}");
+        assert_eq!(stderr_lines(&output), [format!("Error: {error}")]);
+        assert!(server.requests().is_empty());
+        assert!(!dir.join("errors.log").exists());
+    }
+}
+
+#[test]
+fn expiring_tokens_are_warned_about() {
+    let dir = workdir("expiring_tokens_are_warned_about");
+    let user = r#"{"id":1,"name_id":"test-user","username":"Test User"}"#;
+    let server = MockModio::start([(ME, Response::json(200, user))]);
+    let expires = Utc::now() + TimeDelta::days(3) + TimeDelta::hours(1);
+    let claims = format!(r#"{{"sub":"1","exp":{}}}"#, expires.timestamp());
+    let jwt = [r#"{"alg":"RS256","typ":"JWT"}"#, &claims, "signature"]
+        .map(|part| URL_SAFE_NO_PAD.encode(part))
+        .join(".");
+    std::fs::write(dir.join("token.txt"), jwt).unwrap();
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &[]);
+
+    let date = expires.format("%Y-%m-%d %H:%M UTC");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr_lines(&output)[0],
+        format!("        WARN access token expires {date}, in 3 days")
+    );
+    assert_eq!(
+        errors_log(&dir),
+        format!("INFO  token      expires {date}\nERROR 404        {SANDBOX}\n")
+    );
+}
+
+#[test]
+fn expired_tokens_are_refused() {
+    let dir = workdir("expired_tokens_are_refused");
+    let server = MockModio::start([]);
+    std::fs::write(dir.join("token.txt"), "test-token\nexpires=2024-06-01T12:00:00Z\n").unwrap();
+
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &[]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stderr_lines(&output), ["Error: access token expired on 2024-06-01 12:00 UTC"]);
+    assert!(server.requests().is_empty());
+    assert!(!dir.join("errors.log").exists());
+}
+
+#[test]
+fn credentials_can_be_given_without_a_token_file() {
+    for source in ["env", "stdin", "credentials_file"] {
+        let dir = workdir(&format!("credentials_can_be_given_without_a_token_file_{source}"));
+        let server = MockModio::start([(
+            "sandbox-utilities",
+            Response::mods(&[Mod::new(1, "sandbox-utilities")]),
+        )]);
+        std::fs::write(dir.join("mods.txt"), format!("{SANDBOX}\n")).unwrap();
+        let mut command = command(&dir);
+        command.args(["--api-base", &server.api_base()]);
+        match source {
+            "env" => {
+                command.env("MODIO_ACCESS_TOKEN", TOKEN);
+            }
+            "stdin" => {
+                command.args(["--access-token", "-"]).stdin(Stdio::piped());
+            }
+            _ => {
+                let config = dir.join("config/modio-modcheck");
+                std::fs::create_dir_all(&config).unwrap();
+                std::fs::write(
+                    config.join("credentials.toml"),
+                    format!("access_token = \"{TOKEN}\"\n"),
+                )
+                .unwrap();
+            }
+        }
+
+        let mut child =
+            command.arg("mods.txt").stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(format!("{TOKEN}\n").as_bytes()).unwrap();
+        }
+        let output = child.wait_with_output().unwrap();
+
+        assert_eq!(output.status.code(), Some(0), "{source}");
+        assert_eq!(stderr_lines(&output), ["check completed, writing log to `errors.log`"]);
+        assert_eq!(errors_log(&dir), "");
+    }
+}
+
+#[test]
+fn user_id_is_inferred_from_the_access_token() {
+    let dir = workdir("user_id_is_inferred_from_the_access_token");
+    let server = MockModio::start([(
+        "sandbox-utilities",
+        Response::mods(&[Mod::new(1, "sandbox-utilities")]),
+    )]);
+    std::fs::write(dir.join("mods.txt"), format!("{SANDBOX}\n")).unwrap();
+
+    let output = command(&dir)
+        .env("MODIO_ACCESS_TOKEN", TOKEN)
+        .args(["--global-api-base", &server.api_base()])
+        .args(["--api-base", &server.api_base().replace("/v1", "/u-{user_id}/v1")])
+        .arg("mods.txt")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        server.requests(),
+        [format!("/u-1/v1/games/{DRG}/mods?name_id-in=sandbox-utilities&visible-in=0,1&status-in=0,1,3&_offset=0&_limit=100")]
+    );
+}
+
+#[test]
+fn user_id_of_credentials_file_is_checked() {
+    let dir = workdir("user_id_of_credentials_file_is_checked");
+    let server = MockModio::start([]);
+    std::fs::write(dir.join("mods.txt"), format!("{SANDBOX}\n")).unwrap();
+    let config = dir.join("config/modio-modcheck");
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(
+        config.join("credentials.toml"),
+        format!("access_token = \"{TOKEN}\"\nuser_id = 2\nexpires = 2024-06-01T12:00:00Z\n"),
+    )
+    .unwrap();
+
+    let output =
+        command(&dir).args(["--api-base", &server.api_base(), "mods.txt"]).output().unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stderr_lines(&output), ["Error: access token expired on 2024-06-01 12:00 UTC"]);
+
+    std::fs::write(
+        config.join("credentials.toml"),
+        format!("access_token = \"{TOKEN}\"\nuser_id = 2\n"),
+    )
+    .unwrap();
+
+    let output =
+        command(&dir).args(["--api-base", &server.api_base(), "mods.txt"]).output().unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stderr_lines(&output),
+        ["Error: access token belongs to user 1 (`test-user`), not to user 2"]
+    );
+}
+
+#[test]
+fn expiry_in_credentials_file_is_read() {
+    let dir = workdir("expiry_in_credentials_file_is_read");
+    let server = MockModio::start([]);
+    std::fs::write(dir.join("mods.txt"), format!("{SANDBOX}\n")).unwrap();
+    let credentials = dir.join("config/modio-modcheck/credentials.toml");
+    std::fs::create_dir_all(credentials.parent().unwrap()).unwrap();
+
+    for (expires, expired) in [
+        ("2024-06-01", "2024-06-01 00:00 UTC"),
+        ("2024-06-01T12:00:00", "2024-06-01 12:00 UTC"),
+        ("2024-06-01T14:00:00+02:00", "2024-06-01 12:00 UTC"),
+        ("1717243200", "2024-06-01 12:00 UTC"),
+        ("\"2024-06-01T12:00:00Z\"", "2024-06-01 12:00 UTC"),
+    ] {
+        std::fs::write(&credentials, format!("access_token = \"{TOKEN}\"\nexpires = {expires}\n"))
+            .unwrap();
+
+        let output =
+            command(&dir).args(["--api-base", &server.api_base(), "mods.txt"]).output().unwrap();
+
+        assert_eq!(output.status.code(), Some(3), "{expires}");
+        assert_eq!(stderr_lines(&output), [format!("Error: access token expired on {expired}")]);
+    }
+}
+
+#[test]
+fn invalid_expiry_in_credentials_file_is_reported() {
+    let dir = workdir("invalid_expiry_in_credentials_file_is_reported");
+    let server = MockModio::start([]);
+    std::fs::write(dir.join("mods.txt"), format!("{SANDBOX}\n")).unwrap();
+    let credentials = dir.join("config/modio-modcheck/credentials.toml");
+    std::fs::create_dir_all(credentials.parent().unwrap()).unwrap();
+
+    for expires in ["\"tomorrow\"", "1.5", "12:00:00"] {
+        std::fs::write(&credentials, format!("access_token = \"{TOKEN}\"\nexpires = {expires}\n"))
+            .unwrap();
+
+        let output =
+            command(&dir).args(["--api-base", &server.api_base(), "mods.txt"]).output().unwrap();
+
+        assert_eq!(output.status.code(), Some(2), "{expires}");
+        assert_eq!(
+            stderr_lines(&output)[0],
+            format!("Error: invalid credentials file `{}`", credentials.display())
+        );
+    }
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn missing_credentials_are_an_input_error() {
+    let dir = workdir("missing_credentials_are_an_input_error");
+    let server = MockModio::start([]);
+    std::fs::write(dir.join("mods.txt"), format!("{SANDBOX}\n")).unwrap();
+
+    let output =
+        command(&dir).args(["--api-base", &server.api_base(), "mods.txt"]).output().unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    let credentials = dir.join("config/modio-modcheck/credentials.toml");
+    assert_eq!(
+        stderr_lines(&output),
+        [format!(
+            "Error: no access token given, pass `--access-token`, set `MODIO_ACCESS_TOKEN` or create `{}`",
+            credentials.display()
+        )]
+    );
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+#[cfg(unix)]
+fn report_is_written_next_to_the_mod_list_if_the_current_directory_is_read_only() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    let dir =
+        workdir("report_is_written_next_to_the_mod_list_if_the_current_directory_is_read_only");
+    let server = MockModio::start([("missing", Response::mods(&[]))]);
+    let (cwd, lists) = (dir.join("cwd"), dir.join("lists"));
+    std::fs::create_dir_all(&cwd).unwrap();
+    std::fs::create_dir_all(&lists).unwrap();
+    std::fs::write(lists.join("mods.txt"), format!("{MISSING}\n")).unwrap();
+    std::fs::write(cwd.join("token.txt"), format!("{TOKEN}\n")).unwrap();
+    // A bare file name, which only resolves to the mod list's directory through the link.
+    symlink("../lists/mods.txt", cwd.join("mods.txt")).unwrap();
+    let permissions = |mode| std::fs::Permissions::from_mode(mode);
+    std::fs::set_permissions(&cwd, permissions(0o555)).unwrap();
+    if std::fs::write(cwd.join("probe"), "").is_ok() {
+        // Permissions aren't enforced, e.g. when running as root.
+        std::fs::set_permissions(&cwd, permissions(0o755)).unwrap();
+        return;
+    }
+
+    let output = command(&cwd)
+        .args(["--id", "1", "--access-token", "token.txt", "--api-base", &server.api_base()])
+        .arg("mods.txt")
+        .output()
+        .unwrap();
+    std::fs::set_permissions(&cwd, permissions(0o755)).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        std::fs::read_to_string(lists.join("errors.log")).unwrap(),
+        format!("ERROR 404        {MISSING}\n")
+    );
+}
+
+#[test]
+fn previous_report_is_kept() {
+    let dir = workdir("previous_report_is_kept");
+    let server = MockModio::start([("missing", Response::mods(&[]))]);
+
+    run(&dir, &server, &format!("{MISSING}\n"), &[]);
+    let output = run(&dir, &server, &format!("{SANDBOX}\n"), &["--output", "errors.log"]);
+
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            format!("       ERROR 404 {SANDBOX}"),
+            "keeping previous report as `errors.previous.log`".to_string(),
+            "check completed, writing log to `errors.log`".to_string(),
+        ]
+    );
+    assert_eq!(errors_log(&dir), format!("ERROR 404        {SANDBOX}\n"));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("errors.previous.log")).unwrap(),
+        format!("ERROR 404        {MISSING}\n")
+    );
+
+    let output = run(&dir, &server, "", &[]);
+
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            "keeping previous report as `errors.previous-1.log`",
+            "check completed, writing log to `errors.log`",
+        ]
+    );
+    assert_eq!(errors_log(&dir), "");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("errors.previous.log")).unwrap(),
+        format!("ERROR 404        {MISSING}\n")
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("errors.previous-1.log")).unwrap(),
+        format!("ERROR 404        {SANDBOX}\n")
+    );
+}
+
+#[test]
+fn report_name_can_be_timestamped() {
+    let dir = workdir("report_name_can_be_timestamped");
+    let server = MockModio::start([("missing", Response::mods(&[]))]);
+
+    let output = run(&dir, &server, &format!("{MISSING}\n"), &["-o", "out.txt", "--timestamp"]);
+
+    let re = regex::Regex::new(
+        r"^check completed, writing log to `(out-\d{4}-\d{2}-\d{2}T\d{2}-\d{2}-\d{2}\.txt)`$",
+    )
+    .unwrap();
+    let lines = stderr_lines(&output);
+    let report = &re.captures(lines.last().unwrap()).unwrap()[1];
+    assert_eq!(
+        std::fs::read_to_string(dir.join(report)).unwrap(),
+        format!("ERROR 404        {MISSING}\n")
+    );
+    assert!(!dir.join("out.txt").exists());
+}
+
+#[test]
+fn lenient_mod_references_are_accepted() {
+    let dir = workdir("lenient_mod_references_are_accepted");
+    let server = MockModio::start([
+        ("sandbox-utilities", Response::mods(&[Mod::new(1, "sandbox-utilities")])),
+        ("a", Response::mods(&[Mod::new(2, "a")])),
+        ("b", Response::mods(&[Mod::new(3, "b")])),
+        ("c", Response::mods(&[Mod::new(4, "c")])),
+    ]);
+    let mod_list = [
+        "https://mod.io/g/drg/m/sandbox-utilities  ",
+        "http://www.mod.io/g/drg/m/a/",
+        "mod.io/g/drg/m/b?preview=abc#3",
+        "c",
+        "1",
+    ];
+
+    let output = run(&dir, &server, &mod_list.join("\n"), &["--format", "json"]);
+
+    assert!(output.status.success());
+    assert_eq!(stderr_lines(&output), ["check completed, writing log to `errors.log`"]);
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("errors.log")).unwrap()).unwrap();
+    let refs = report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|check| {
+            assert_eq!(check["outcome"], "ok");
+            (check["url"].clone(), check["name_id"].clone(), check["mod_id"].clone())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        refs,
+        [
+            (
+                json!("https://mod.io/g/drg/m/sandbox-utilities"),
+                json!("sandbox-utilities"),
+                json!(null)
+            ),
+            (json!("http://www.mod.io/g/drg/m/a/"), json!("a"), json!(null)),
+            (json!("mod.io/g/drg/m/b?preview=abc#3"), json!("b"), json!(3)),
+            (json!("c"), json!("c"), json!(null)),
+            (json!("1"), json!(null), json!(1)),
+        ]
+    );
+    let mut requests = server.requests();
+    requests.sort();
+    assert_eq!(
+        requests,
+        [
+            "/v1/games/2475/mods?id-in=3,1&visible-in=0,1&status-in=0,1,3&_offset=0&_limit=100",
+            "/v1/games/2475/mods?name_id-in=sandbox-utilities,a,b,c&visible-in=0,1&status-in=0,1,3&_offset=0&_limit=100",
+        ]
+    );
+}
+
+#[test]
+fn duplicate_mods_are_checked_once() {
+    let dir = workdir("duplicate_mods_are_checked_once");
+    let server = MockModio::start([(
+        "sandbox-utilities",
+        Response::mods(&[Mod::new(1, "sandbox-utilities").modfiles(&[10, 11])]),
+    )]);
+    let mod_list = format!("{SANDBOX}\n{MISSING}\n{SANDBOX}#1\n{SANDBOX}\n{SANDBOX}#1/11\n");
+
+    let output = run(&dir, &server, &mod_list, &[]);
+
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            format!("   DUPLICATE line 3: {SANDBOX}#1 (same mod as line 1)"),
+            format!("   DUPLICATE line 4: {SANDBOX} (same mod as line 1)"),
+            format!(
+                "   DUPLICATE line 5: {SANDBOX}#1/11 (different modfile of the same mod as line 1)"
+            ),
+            format!("       ERROR 404 {MISSING}"),
+            "check completed, writing log to `errors.log`".to_string(),
+        ]
+    );
+    assert_eq!(
+        errors_log(&dir),
+        format!(
+            "ERROR 404        {MISSING}\n\
+             WARN  line 3     {SANDBOX}#1 (same mod as line 1)\n\
+             WARN  line 4     {SANDBOX} (same mod as line 1)\n\
+             WARN  line 5     {SANDBOX}#1/11 (different modfile of the same mod as line 1)\n"
+        )
+    );
+    assert_eq!(
+        server.requests(),
+        ["/v1/games/2475/mods?name_id-in=sandbox-utilities,missing&visible-in=0,1&status-in=0,1,3&_offset=0&_limit=100"]
+    );
+}
+
+#[test]
+fn mods_of_other_games_are_checked_with_game() {
+    let dir = workdir("mods_of_other_games_are_checked_with_game");
+    let server = MockModio::start([("a", Response::mods(&[Mod::new(1, "a")]))]);
+    let mod_list = format!("https://mod.io/g/other-game/m/a\nb\n{SANDBOX}\n");
+
+    let output = run(&dir, &server, &mod_list, &["--game", "other-game"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr_lines(&output),
+        [
+            "       ERROR 404 b".to_string(),
+            format!("       ERROR   - {SANDBOX}"),
+            "check completed, writing log to `errors.log`".to_string(),
+        ]
+    );
+    assert_eq!(errors_log(&dir), format!("ERROR 404        b\nERROR game       {SANDBOX}\n"));
+    assert_eq!(
+        server.requests(),
+        [
+            "/v1/games?name_id=other-game",
+            "/v1/games/5/mods?name_id-in=a,b&visible-in=0,1&status-in=0,1,3&_offset=0&_limit=100",
+        ]
+    );
+
+    let output = run(&dir, &server, &mod_list, &["--game", "5"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(server.requests()[2], "/v1/games/5");
+
+    let output = run(&dir, &server, &mod_list, &["--game", "missing"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stderr_lines(&output), ["Error: game `missing` not found on mod.io"]);
+}
@@ -0,0 +1,456 @@
+//! Local stand-in for the mod.io API, serving canned responses over plain HTTP.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub const DRG: u32 = 2475;
+
+/// Route of the token check, to register responses for instead of the default [`USER`].
+pub const ME: &str = "/me";
+
+/// Access token accepted by the default token check.
+pub const TOKEN: &str = "test-token";
+
+/// User the access token belongs to by default, as `(id, name_id, username)`.
+pub const USER: (u64, &str, &str) = (1, "test-user", "Test User");
+
+/// A canned HTTP response.
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Body,
+}
+
+#[derive(Debug, Clone)]
+pub enum Body {
+    /// Mods to include in a `Mods` page, always served with `200 OK`.
+    Mods(Vec<Mod>),
+    Raw(String),
+}
+
+impl Response {
+    pub fn json(status: u16, body: impl Into<String>) -> Self {
+        Response { status, headers: vec![], body: Body::Raw(body.into()) }
+    }
+
+    /// `200 OK` with a `Mods` payload containing the given mods.
+    pub fn mods(mods: &[Mod]) -> Self {
+        Response { status: 200, headers: vec![], body: Body::Mods(mods.to_vec()) }
+    }
+
+    /// mod.io error envelope with the given status.
+    pub fn error(status: u16, error_ref: u32, message: &str) -> Self {
+        Response::json(
+            status,
+            format!(
+                r#"{{"error":{{"code":{status},"error_ref":{error_ref},"message":"{message}"}}}}"#
+            ),
+        )
+    }
+
+    pub fn header(mut self, name: &str, value: impl ToString) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Mod {
+    pub id: u32,
+    pub name_id: String,
+    pub status: u32,
+    pub visible: u32,
+    pub modfiles: Vec<u32>,
+}
+
+impl Mod {
+    pub fn new(id: u32, name_id: &str) -> Self {
+        Mod { id, name_id: name_id.to_string(), status: 1, visible: 1, modfiles: vec![] }
+    }
+
+    pub fn status(mut self, status: u32) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn hidden(mut self) -> Self {
+        self.visible = 0;
+        self
+    }
+
+    pub fn modfiles(mut self, modfiles: &[u32]) -> Self {
+        self.modfiles = modfiles.to_vec();
+        self
+    }
+
+    pub fn profile_url(&self) -> String {
+        format!("https://mod.io/g/drg/m/{}", self.name_id)
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            r#"{{"id":{},"game_id":{DRG},"name_id":"{}","status":{},"visible":{},"profile_url":"{}"}}"#,
+            self.id,
+            self.name_id,
+            self.status,
+            self.visible,
+            self.profile_url()
+        )
+    }
+}
+
+/// mod.io stand-in listening on a random local port.
+///
+/// `/v1/games/2475/mods?name_id-in=<name_ids>` is answered by combining the responses registered
+/// for each of the `<name_ids>`: the first error response wins, otherwise the mods of all responses
+/// are paginated according to `_offset` and `_limit`. Responses registered for the same name_id are
+/// served in order, repeating the last one; unknown name_ids have no mods.
+///
+/// `/v1/games/2475/mods?id-in=<ids>` and `/v1/games/2475/mods/<id>/files/<modfile_id>` are answered
+/// from the mods of the current response of every name_id. Mods are served for all [`GAMES`] alike.
+///
+/// `/v1/games?name_id=<name_id>` and `/v1/games/<id>` are answered from [`GAMES`], `/v1/me` with
+/// the responses registered for [`ME`], or with [`USER`] if the request carries [`TOKEN`]. Everything
+/// else is a `404`. All routes are also served below a per-user `/u-<user_id>` prefix.
+pub struct MockModio {
+    port: u16,
+    requests: Arc<Mutex<Vec<String>>>,
+    state: Arc<Mutex<State>>,
+    in_flight: Arc<InFlight>,
+}
+
+/// Number of requests being handled, now and at most.
+#[derive(Default)]
+struct InFlight {
+    now: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+struct State {
+    routes: HashMap<String, Vec<Response>>,
+    page_size: usize,
+    /// How long to wait before sending each response.
+    delay: Duration,
+}
+
+impl MockModio {
+    pub fn start(responses: impl IntoIterator<Item = (&'static str, Response)>) -> Self {
+        MockModio::with_page_size(responses, 100)
+    }
+
+    /// Like [`MockModio::start`], but return at most `page_size` mods per page.
+    pub fn with_page_size(
+        responses: impl IntoIterator<Item = (&'static str, Response)>,
+        page_size: usize,
+    ) -> Self {
+        let mut routes = HashMap::<String, Vec<Response>>::new();
+        for (name_id, response) in responses {
+            routes.entry(name_id.to_string()).or_default().push(response);
+        }
+        let state = Arc::new(Mutex::new(State { routes, page_size, delay: Duration::ZERO }));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(vec![]));
+        let in_flight = Arc::new(InFlight::default());
+
+        let (shared, log, counter) =
+            (Arc::clone(&state), Arc::clone(&requests), Arc::clone(&in_flight));
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { break };
+                let (state, log, counter) =
+                    (Arc::clone(&shared), Arc::clone(&log), Arc::clone(&counter));
+                std::thread::spawn(move || {
+                    let target = handle(stream, &state, &counter);
+                    if target != "/v1/me" {
+                        log.lock().unwrap().push(target);
+                    }
+                });
+            }
+        });
+
+        MockModio { port, requests, state, in_flight }
+    }
+
+    /// Wait `delay` before sending each response.
+    pub fn delay_responses(&self, delay: Duration) {
+        self.state.lock().unwrap().delay = delay;
+    }
+
+    /// Highest number of requests handled at the same time so far.
+    pub fn peak_in_flight(&self) -> usize {
+        self.in_flight.peak.load(Ordering::SeqCst)
+    }
+
+    pub fn api_base(&self) -> String {
+        format!("http://127.0.0.1:{}/v1", self.port)
+    }
+
+    /// Request targets (path and decoded query) received so far, except for token checks.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Games known to [`MockModio`], as `(id, name_id, name)`.
+pub const GAMES: [(u32, &str, &str); 2] =
+    [(2475, "drg", "Deep Rock Galactic"), (5, "other-game", "Other Game")];
+
+impl State {
+    fn respond(
+        &mut self,
+        path: &str,
+        query: &HashMap<String, String>,
+        authorization: Option<&str>,
+    ) -> Response {
+        let not_found = Response::error(404, 14000, "The requested resource could not be found.");
+        let game_json = |(id, name_id, name): (u32, &str, &str)| {
+            format!(r#"{{"id":{id},"name_id":"{name_id}","name":"{name}"}}"#)
+        };
+        if path == "/v1/me" {
+            if self.routes.contains_key(ME) {
+                return self.next_response(ME);
+            }
+            if authorization == Some(&format!("Bearer {TOKEN}")) {
+                let (id, name_id, username) = USER;
+                let user =
+                    format!(r#"{{"id":{id},"name_id":"{name_id}","username":"{username}"}}"#);
+                return Response::json(200, user);
+            }
+            return Response::error(401, 11000, "Authentication required.");
+        }
+        if path == "/v1/games" {
+            let games = GAMES
+                .into_iter()
+                .filter(|game| Some(game.1) == query.get("name_id").map(String::as_str));
+            let data = games.map(game_json).collect::<Vec<_>>();
+            return Response::json(200, format!(r#"{{"data":[{}]}}"#, data.join(",")));
+        }
+        let Some((game_id, path)) =
+            path.strip_prefix("/v1/games/").map(|rest| rest.split_once('/').unwrap_or((rest, "")))
+        else {
+            return not_found;
+        };
+        let Some(game) = GAMES.into_iter().find(|game| game.0.to_string() == game_id) else {
+            return Response::error(404, 14001, "The requested game could not be found.");
+        };
+        if path.is_empty() {
+            return Response::json(200, game_json(game));
+        }
+
+        if let Some((mod_id, modfile_id)) =
+            path.strip_prefix("mods/").and_then(|rest| rest.split_once("/files/"))
+        {
+            let (mod_id, modfile_id): (u32, u32) =
+                (mod_id.parse().unwrap(), modfile_id.parse().unwrap());
+            let exists = self.mods().any(|m| m.id == mod_id && m.modfiles.contains(&modfile_id));
+            return match exists {
+                true => Response::json(200, format!(r#"{{"id":{modfile_id},"mod_id":{mod_id}}}"#)),
+                false => Response::error(404, 15010, "The requested modfile could not be found."),
+            };
+        }
+
+        match (path, query.get("name_id-in"), query.get("id-in")) {
+            ("mods", None, Some(ids)) => {
+                let ids = ids.split(',').map(|id| id.parse().unwrap()).collect::<Vec<u32>>();
+                let mods = self.mods().filter(|m| ids.contains(&m.id)).cloned().collect();
+                self.page(mods, query)
+            }
+            ("mods", Some(name_ids), None) => {
+                let responses = name_ids.split(',').map(|name_id| self.next_response(name_id));
+                let mut mods = vec![];
+                let mut headers = vec![];
+                for response in responses.collect::<Vec<_>>() {
+                    match response.body {
+                        Body::Mods(page) => mods.extend(page),
+                        Body::Raw(_) => return response,
+                    }
+                    headers.extend(response.headers);
+                }
+                Response { headers, ..self.page(mods, query) }
+            }
+            _ => not_found,
+        }
+    }
+
+    /// Mods of the current response of every name_id.
+    fn mods(&self) -> impl Iterator<Item = &Mod> {
+        self.routes.values().flat_map(|responses| match &responses[0].body {
+            Body::Mods(mods) => mods.as_slice(),
+            Body::Raw(_) => &[],
+        })
+    }
+
+    fn next_response(&mut self, name_id: &str) -> Response {
+        match self.routes.get_mut(name_id) {
+            Some(responses) if responses.len() > 1 => responses.remove(0),
+            Some(responses) => responses[0].clone(),
+            None => Response::mods(&[]),
+        }
+    }
+
+    /// Like mod.io, only include hidden and not accepted mods if asked to with the `visible-in` and
+    /// `status-in` filters.
+    fn page(&self, mods: Vec<Mod>, query: &HashMap<String, String>) -> Response {
+        let included = |filter: &str, value: u32| {
+            let values = query.get(filter).map_or("1", String::as_str);
+            values.split(',').any(|v| v == value.to_string())
+        };
+        let mods = mods
+            .into_iter()
+            .filter(|m| included("visible-in", m.visible) && included("status-in", m.status))
+            .collect::<Vec<_>>();
+        let offset = query.get("_offset").map_or(0, |o| o.parse().unwrap());
+        let limit = query.get("_limit").map_or(100, |l| l.parse().unwrap()).min(self.page_size);
+        let data = mods.iter().skip(offset).take(limit).map(Mod::to_json).collect::<Vec<_>>();
+        Response::json(
+            200,
+            format!(
+                r#"{{"data":[{}],"result_count":{},"result_offset":{offset},"result_limit":{limit},"result_total":{}}}"#,
+                data.join(","),
+                data.len(),
+                mods.len()
+            ),
+        )
+    }
+}
+
+fn handle(mut stream: TcpStream, state: &Mutex<State>, in_flight: &InFlight) -> String {
+    let mut reader = BufReader::new(&mut stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut authorization = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).unwrap() == 0 || header == "\r\n" {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("authorization") {
+                authorization = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let target = percent_decode(target);
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let path = match path.strip_prefix("/u-").and_then(|rest| rest.split_once('/')) {
+        Some((user_id, rest)) if user_id.parse::<u64>().is_ok() => {
+            &path[path.len() - rest.len() - 1..]
+        }
+        _ => path,
+    };
+    let now = in_flight.now.fetch_add(1, Ordering::SeqCst) + 1;
+    in_flight.peak.fetch_max(now, Ordering::SeqCst);
+    let (response, delay) = {
+        let mut state = state.lock().unwrap();
+        (state.respond(path, &query, authorization.as_deref()), state.delay)
+    };
+    std::thread::sleep(delay);
+    // Before responding, as the client may send its next request as soon as it has the response.
+    in_flight.now.fetch_sub(1, Ordering::SeqCst);
+
+    let Body::Raw(body) = &response.body else { unreachable!("mods are always paginated") };
+    let mut out = format!(
+        "HTTP/1.1 {} Mock\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n",
+        response.status,
+        body.len()
+    );
+    for (name, value) in &response.headers {
+        out.push_str(&format!("{name}: {value}\r\n"));
+    }
+    out.push_str("\r\n");
+    out.push_str(body);
+    stream.write_all(out.as_bytes()).unwrap();
+
+    target
+}
+
+fn percent_decode(s: &str) -> String {
+    let mut out = vec![];
+    let mut bytes = s.bytes();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'%' => {
+                let hex = [bytes.next().unwrap(), bytes.next().unwrap()];
+                out.push(u8::from_str_radix(std::str::from_utf8(&hex).unwrap(), 16).unwrap());
+            }
+            b => out.push(b),
+        }
+    }
+    String::from_utf8(out).unwrap()
+}
+
+/// Scratch directory for a single test, containing a token file.
+pub fn workdir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("token.txt"), format!("{TOKEN}\n")).unwrap();
+    dir
+}
+
+/// Run `modio-modcheck` in `dir` against `server` with `mod_list` as the list of mods.
+pub fn run(dir: &Path, server: &MockModio, mod_list: &str, args: &[&str]) -> Output {
+    run_subcommand(dir, server, &[], mod_list, args)
+}
+
+/// Like [`run`], but run the given `subcommand` instead of the default one.
+pub fn run_subcommand(
+    dir: &Path,
+    server: &MockModio,
+    subcommand: &[&str],
+    mod_list: &str,
+    args: &[&str],
+) -> Output {
+    std::fs::write(dir.join("mods.txt"), mod_list).unwrap();
+    command(dir)
+        .args(subcommand)
+        .args(["--id", "1", "--access-token", "token.txt", "--api-base", &server.api_base()])
+        .args(args)
+        .arg("mods.txt")
+        .output()
+        .unwrap()
+}
+
+/// `modio-modcheck` running in `dir`, without any credentials, and with `dir/config` as the config
+/// directory.
+pub fn command(dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_modio-modcheck"));
+    command
+        .current_dir(dir)
+        .env_remove("MODIO_API_BASE")
+        .env_remove("MODIO_GLOBAL_API_BASE")
+        .env_remove("MODIO_ACCESS_TOKEN")
+        .env_remove("RUST_LOG")
+        .env_remove("CLICOLOR_FORCE")
+        .env_remove("RUST_BACKTRACE")
+        .env_remove("RUST_LIB_BACKTRACE")
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .env("HOME", dir);
+    command
+}
+
+pub fn stderr_lines(output: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stderr).lines().map(str::to_string).collect()
+}
+
+pub fn errors_log(dir: &Path) -> String {
+    std::fs::read_to_string(dir.join("errors.log")).unwrap()
+}